@@ -1,20 +1,48 @@
 use gandiva_rust_udf_macro::udf;
-use radix_fmt::radix;
+use num_bigint::BigInt;
+use num_traits::{Num, Signed};
 
+// Enough for a 1024 bit number in binary, while keeping parsing cheap.
+const MAX_NUM_LENGTH: usize = 1024;
+
+// Follows MySQL's CONV: num may be an integer of any width, a negative to_radix
+// gives signed output and otherwise negative numbers are shown as their 64 bit
+// two's complement.
 #[udf]
 pub fn conv(num: &str, from_radix: i64, to_radix: i64) -> Result<String, String> {
-    if (from_radix < 2 || from_radix > 36) || (to_radix < 2 || to_radix > 36) {
+    let valid_radix = |radix: i64| (2..=36).contains(&radix.unsigned_abs());
+    if !valid_radix(from_radix) || !valid_radix(to_radix) {
         // return an error with the actual radix given as part of the error message
         return Err(format!(
             "Radix must be between 2 and 36, got from_radix: {}, to_radix: {}",
             from_radix, to_radix
         ));
     }
-    let value = i64::from_str_radix(num, from_radix as u32);
-    match value {
-        Ok(v) => Ok(radix(v, to_radix as u8).to_string()),
-        Err(e) => Err(e.to_string()),
+    if num.len() > MAX_NUM_LENGTH {
+        return Err(format!(
+            "Number must be at most {} characters, got length: {}",
+            MAX_NUM_LENGTH,
+            num.len()
+        ));
+    }
+    // num-bigint skips underscores, which MySQL does not accept as digits
+    if num.contains('_') {
+        return Err("invalid digit found in string".to_string());
+    }
+    let value =
+        BigInt::from_str_radix(num, from_radix.unsigned_abs() as u32).map_err(|e| e.to_string())?;
+    let output_radix = to_radix.unsigned_abs() as u32;
+    if to_radix > 0 && value.is_negative() {
+        if value < BigInt::from(i64::MIN) {
+            return Err(format!(
+                "Negative number does not fit in 64 bits, use a negative to_radix for signed output: {}",
+                num
+            ));
+        }
+        let twos_complement = value + (BigInt::from(1u8) << 64u32);
+        return Ok(twos_complement.to_str_radix(output_radix));
     }
+    Ok(value.to_str_radix(output_radix))
 }
 
 #[cfg(test)]
@@ -85,6 +113,23 @@ mod conv_tests {
         assert_conv_err("2", 2, 10, "invalid digit found in string");
     }
 
+    #[test]
+    fn test_str_underscore() {
+        assert_conv_err("1_000", 10, 10, "invalid digit found in string");
+        assert_conv_err("_1", 16, 10, "invalid digit found in string");
+    }
+
+    #[test]
+    fn test_str_too_long() {
+        assert_conv(&"1".repeat(1024), 2, -2, &"1".repeat(1024));
+        assert_conv_err(
+            &"1".repeat(1025),
+            2,
+            10,
+            "Number must be at most 1024 characters, got length: 1025",
+        );
+    }
+
     #[test]
     fn test_from_radix_too_large() {
         assert_conv_err(
@@ -94,4 +139,46 @@ mod conv_tests {
             "Radix must be between 2 and 36, got from_radix: 37, to_radix: 10",
         );
     }
+
+    #[test]
+    fn test_conv_above_i64() {
+        assert_conv("18446744073709551615", 10, 16, "ffffffffffffffff");
+        assert_conv("ffffffffffffffff", 16, 10, "18446744073709551615");
+        assert_conv(
+            "20010db8000000000000000000000001",
+            16,
+            10,
+            "42540766411282592856903984951653826561",
+        );
+    }
+
+    #[test]
+    fn test_conv_negative_unsigned_output() {
+        assert_conv("-1", 10, 16, "ffffffffffffffff");
+        assert_conv("-9223372036854775808", 10, 16, "8000000000000000");
+        assert_conv_err(
+            "-9223372036854775809",
+            10,
+            16,
+            "Negative number does not fit in 64 bits, use a negative to_radix for signed output: -9223372036854775809",
+        );
+    }
+
+    #[test]
+    fn test_conv_negative_to_radix() {
+        assert_conv("-17", 10, -18, "-h");
+        assert_conv("ff", 16, -10, "255");
+        assert_conv("-ff", -16, -10, "-255");
+        assert_conv("-100000000000000000000", 10, -16, "-56bc75e2d63100000");
+    }
+
+    #[test]
+    fn test_to_radix_too_small() {
+        assert_conv_err(
+            "2",
+            10,
+            -1,
+            "Radix must be between 2 and 36, got from_radix: 10, to_radix: -1",
+        );
+    }
 }
@@ -0,0 +1,217 @@
+// AES-GCM, AES-CBC and ChaCha20-Poly1305 with exact size keys, plus MySQL's
+// AES_ENCRYPT for compatibility. Random IVs and nonces are prepended to the
+// ciphertext.
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{BlockCipher, BlockDecryptMut, BlockEncryptMut, KeyInit, KeyIvInit};
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::Aead;
+use aes_gcm::AesGcm;
+use chacha20poly1305::ChaCha20Poly1305;
+use rand::RngCore;
+
+const BLOCK_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const DECRYPTION_FAILED: &str = "Decryption failed, wrong key or corrupted ciphertext";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BlockMode {
+    Ecb,
+    Cbc,
+    Gcm,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mode {
+    block_mode: BlockMode,
+    key_len: usize,
+}
+
+impl Mode {
+    pub const DEFAULT: Mode = Mode {
+        block_mode: BlockMode::Gcm,
+        key_len: 32,
+    };
+
+    // Modes such as "aes-256-gcm", "aes-128-cbc", or just "gcm" for a 256 bit
+    // key like the default. "mysql" is MySQL's default AES_ENCRYPT, AES-128-ECB with the key
+    // folded to 16 bytes. ECB shows which blocks of the plaintext are equal, so
+    // it is only meant for reading and writing values shared with MySQL.
+    pub fn parse(mode: &str) -> Result<Mode, String> {
+        let normalized = mode.trim().to_ascii_lowercase();
+        if normalized == "mysql" {
+            return Ok(Mode {
+                block_mode: BlockMode::Ecb,
+                key_len: 16,
+            });
+        }
+        let (bits, block_mode) = match normalized.strip_prefix("aes-") {
+            Some(rest) => rest.split_once('-').unwrap_or((rest, "")),
+            None => ("256", normalized.as_str()),
+        };
+        let key_len = match bits {
+            "128" => Some(16),
+            "192" => Some(24),
+            "256" => Some(32),
+            _ => None,
+        };
+        let block_mode = match block_mode {
+            "cbc" => Some(BlockMode::Cbc),
+            "gcm" => Some(BlockMode::Gcm),
+            _ => None,
+        };
+        match (block_mode, key_len) {
+            (Some(block_mode), Some(key_len)) => Ok(Mode {
+                block_mode,
+                key_len,
+            }),
+            _ => Err(format!(
+                "Mode must be like 'aes-256-gcm', 'aes-128-cbc' or 'mysql', got mode: {}",
+                mode
+            )),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self.block_mode {
+            BlockMode::Ecb => "mysql".to_string(),
+            BlockMode::Cbc => format!("aes-{}-cbc", self.key_len * 8),
+            BlockMode::Gcm => format!("aes-{}-gcm", self.key_len * 8),
+        }
+    }
+
+    // The key as used by the cipher. MySQL folds keys of any length, so any
+    // key is accepted to read values MySQL has encrypted.
+    fn key(&self, key: &[u8]) -> Result<Vec<u8>, String> {
+        match self.block_mode {
+            BlockMode::Ecb => Ok(fold_key(key, self.key_len)),
+            _ => exact_key(key, self.key_len, &self.name()),
+        }
+    }
+}
+
+fn exact_key(key: &[u8], key_len: usize, name: &str) -> Result<Vec<u8>, String> {
+    if key.len() != key_len {
+        return Err(format!(
+            "Key must be {} bytes for {}, got {} bytes",
+            key_len,
+            name,
+            key.len()
+        ));
+    }
+    Ok(key.to_vec())
+}
+
+// Folds the key into key_len bytes the way MySQL's AES_ENCRYPT does, by xoring
+// it in cyclically. A key of exactly key_len bytes is used as is.
+fn fold_key(key: &[u8], key_len: usize) -> Vec<u8> {
+    let mut folded = vec![0u8; key_len];
+    for (i, byte) in key.iter().enumerate() {
+        folded[i % key_len] ^= byte;
+    }
+    folded
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+fn ecb_encrypt<C: BlockCipher + BlockEncryptMut + KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    // the key has been folded to the cipher's key size
+    ecb::Encryptor::<C>::new_from_slice(key)
+        .unwrap()
+        .encrypt_padded_vec_mut::<Pkcs7>(data)
+}
+
+fn ecb_decrypt<C: BlockCipher + BlockDecryptMut + KeyInit>(
+    key: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    ecb::Decryptor::<C>::new_from_slice(key)
+        .unwrap()
+        .decrypt_padded_vec_mut::<Pkcs7>(data)
+        .map_err(|_| DECRYPTION_FAILED.to_string())
+}
+
+fn cbc_encrypt<C: BlockCipher + BlockEncryptMut + KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut result = random_bytes(BLOCK_SIZE);
+    let ciphertext = cbc::Encryptor::<C>::new_from_slices(key, &result)
+        .unwrap()
+        .encrypt_padded_vec_mut::<Pkcs7>(data);
+    result.extend_from_slice(&ciphertext);
+    result
+}
+
+fn cbc_decrypt<C: BlockCipher + BlockDecryptMut + KeyInit>(
+    key: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    if data.len() < BLOCK_SIZE {
+        return Err(DECRYPTION_FAILED.to_string());
+    }
+    let (iv, ciphertext) = data.split_at(BLOCK_SIZE);
+    cbc::Decryptor::<C>::new_from_slices(key, iv)
+        .unwrap()
+        .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+        .map_err(|_| DECRYPTION_FAILED.to_string())
+}
+
+fn aead_encrypt<A: Aead + KeyInit>(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut result = random_bytes(NONCE_SIZE);
+    let ciphertext = A::new_from_slice(key)
+        .unwrap()
+        .encrypt(result.as_slice().into(), data)
+        .map_err(|_| "Encryption failed".to_string())?;
+    result.extend_from_slice(&ciphertext);
+    Ok(result)
+}
+
+fn aead_decrypt<A: Aead + KeyInit>(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_SIZE {
+        return Err(DECRYPTION_FAILED.to_string());
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+    A::new_from_slice(key)
+        .unwrap()
+        .decrypt(nonce.into(), ciphertext)
+        .map_err(|_| DECRYPTION_FAILED.to_string())
+}
+
+pub fn aes_encrypt(data: &[u8], key: &[u8], mode: Mode) -> Result<Vec<u8>, String> {
+    let key = mode.key(key)?;
+    let encrypted = match (mode.block_mode, mode.key_len) {
+        (BlockMode::Ecb, _) => ecb_encrypt::<Aes128>(&key, data),
+        (BlockMode::Cbc, 16) => cbc_encrypt::<Aes128>(&key, data),
+        (BlockMode::Cbc, 24) => cbc_encrypt::<Aes192>(&key, data),
+        (BlockMode::Cbc, _) => cbc_encrypt::<Aes256>(&key, data),
+        (BlockMode::Gcm, 16) => aead_encrypt::<AesGcm<Aes128, U12>>(&key, data)?,
+        (BlockMode::Gcm, 24) => aead_encrypt::<AesGcm<Aes192, U12>>(&key, data)?,
+        (BlockMode::Gcm, _) => aead_encrypt::<AesGcm<Aes256, U12>>(&key, data)?,
+    };
+    Ok(encrypted)
+}
+
+pub fn aes_decrypt(data: &[u8], key: &[u8], mode: Mode) -> Result<Vec<u8>, String> {
+    let key = mode.key(key)?;
+    match (mode.block_mode, mode.key_len) {
+        (BlockMode::Ecb, _) => ecb_decrypt::<Aes128>(&key, data),
+        (BlockMode::Cbc, 16) => cbc_decrypt::<Aes128>(&key, data),
+        (BlockMode::Cbc, 24) => cbc_decrypt::<Aes192>(&key, data),
+        (BlockMode::Cbc, _) => cbc_decrypt::<Aes256>(&key, data),
+        (BlockMode::Gcm, 16) => aead_decrypt::<AesGcm<Aes128, U12>>(&key, data),
+        (BlockMode::Gcm, 24) => aead_decrypt::<AesGcm<Aes192, U12>>(&key, data),
+        (BlockMode::Gcm, _) => aead_decrypt::<AesGcm<Aes256, U12>>(&key, data),
+    }
+}
+
+pub fn chacha20poly1305_encrypt(data: &[u8], key: &[u8]) -> Result<Vec<u8>, String> {
+    let key = exact_key(key, 32, "chacha20poly1305")?;
+    aead_encrypt::<ChaCha20Poly1305>(&key, data)
+}
+
+pub fn chacha20poly1305_decrypt(data: &[u8], key: &[u8]) -> Result<Vec<u8>, String> {
+    let key = exact_key(key, 32, "chacha20poly1305")?;
+    aead_decrypt::<ChaCha20Poly1305>(&key, data)
+}
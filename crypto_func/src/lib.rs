@@ -0,0 +1,278 @@
+mod cipher;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use cipher::Mode;
+use gandiva_rust_udf_macro::udf;
+
+// Ciphertexts are base64 encoded, with the random IV or nonce prepended. Keys
+// must be exactly the cipher's key size, e.g. 32 bytes for AES-256-GCM, the
+// default, and for ChaCha20-Poly1305. Keys are utf8, or binary for raw keys
+// such as unhex_binary('00ff..'). Only the "mysql" mode matches MySQL:
+// TO_BASE64(AES_ENCRYPT(text, key)) gives the same value as
+// aes_encrypt(text, key, 'mysql').
+
+fn decode_ciphertext(ciphertext: &str) -> Result<Vec<u8>, String> {
+    STANDARD
+        .decode(ciphertext.trim())
+        .map_err(|_| format!("Invalid base64 ciphertext: {}", ciphertext))
+}
+
+fn to_utf8(bytes: Vec<u8>) -> Result<String, String> {
+    String::from_utf8(bytes).map_err(|_| "Decrypted value is not valid UTF-8".to_string())
+}
+
+#[udf(name = "aes_encrypt")]
+pub fn aes_encrypt_default(plaintext: &str, key: &str) -> Result<String, String> {
+    aes_encrypt_binary_key(plaintext, key.as_bytes())
+}
+
+#[udf(name = "aes_encrypt")]
+pub fn aes_encrypt_binary_key(plaintext: &str, key: &[u8]) -> Result<String, String> {
+    let encrypted = cipher::aes_encrypt(plaintext.as_bytes(), key, Mode::DEFAULT)?;
+    Ok(STANDARD.encode(encrypted))
+}
+
+// mode is such as "aes-256-gcm" or "aes-128-cbc". "mysql" is MySQL's default
+// AES-128-ECB, which leaks which blocks are equal and should only be used to
+// exchange values with MySQL.
+#[udf(name = "aes_encrypt")]
+pub fn aes_encrypt_with_mode(plaintext: &str, key: &str, mode: &str) -> Result<String, String> {
+    aes_encrypt_binary_key_with_mode(plaintext, key.as_bytes(), mode)
+}
+
+#[udf(name = "aes_encrypt")]
+pub fn aes_encrypt_binary_key_with_mode(
+    plaintext: &str,
+    key: &[u8],
+    mode: &str,
+) -> Result<String, String> {
+    let encrypted = cipher::aes_encrypt(plaintext.as_bytes(), key, Mode::parse(mode)?)?;
+    Ok(STANDARD.encode(encrypted))
+}
+
+#[udf(name = "aes_decrypt")]
+pub fn aes_decrypt_default(ciphertext: &str, key: &str) -> Result<String, String> {
+    aes_decrypt_binary_key(ciphertext, key.as_bytes())
+}
+
+#[udf(name = "aes_decrypt")]
+pub fn aes_decrypt_binary_key(ciphertext: &str, key: &[u8]) -> Result<String, String> {
+    let data = decode_ciphertext(ciphertext)?;
+    to_utf8(cipher::aes_decrypt(&data, key, Mode::DEFAULT)?)
+}
+
+#[udf(name = "aes_decrypt")]
+pub fn aes_decrypt_with_mode(ciphertext: &str, key: &str, mode: &str) -> Result<String, String> {
+    aes_decrypt_binary_key_with_mode(ciphertext, key.as_bytes(), mode)
+}
+
+#[udf(name = "aes_decrypt")]
+pub fn aes_decrypt_binary_key_with_mode(
+    ciphertext: &str,
+    key: &[u8],
+    mode: &str,
+) -> Result<String, String> {
+    let data = decode_ciphertext(ciphertext)?;
+    to_utf8(cipher::aes_decrypt(&data, key, Mode::parse(mode)?)?)
+}
+
+#[udf]
+pub fn chacha20poly1305_encrypt(plaintext: &str, key: &str) -> Result<String, String> {
+    chacha20poly1305_encrypt_binary_key(plaintext, key.as_bytes())
+}
+
+#[udf(name = "chacha20poly1305_encrypt")]
+pub fn chacha20poly1305_encrypt_binary_key(plaintext: &str, key: &[u8]) -> Result<String, String> {
+    let encrypted = cipher::chacha20poly1305_encrypt(plaintext.as_bytes(), key)?;
+    Ok(STANDARD.encode(encrypted))
+}
+
+#[udf]
+pub fn chacha20poly1305_decrypt(ciphertext: &str, key: &str) -> Result<String, String> {
+    chacha20poly1305_decrypt_binary_key(ciphertext, key.as_bytes())
+}
+
+#[udf(name = "chacha20poly1305_decrypt")]
+pub fn chacha20poly1305_decrypt_binary_key(ciphertext: &str, key: &[u8]) -> Result<String, String> {
+    let data = decode_ciphertext(ciphertext)?;
+    to_utf8(cipher::chacha20poly1305_decrypt(&data, key)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_128: &str = "0123456789abcdef";
+    const KEY_192: &str = "0123456789abcdef01234567";
+    const KEY_256: &str = "0123456789abcdef0123456789abcdef";
+
+    const MODES: [(&str, &str); 7] = [
+        ("mysql", KEY_128),
+        ("aes-128-cbc", KEY_128),
+        ("aes-192-cbc", KEY_192),
+        ("aes-256-cbc", KEY_256),
+        ("aes-128-gcm", KEY_128),
+        ("aes-192-gcm", KEY_192),
+        ("aes-256-gcm", KEY_256),
+    ];
+
+    #[test]
+    fn test_aes_encrypt_mysql_compatible() {
+        assert_eq!(
+            aes_encrypt_with_mode("MySQL", "0123456789abcdefXYZ", "mysql"),
+            Ok("XID57uH3ajcwUAyL+rrgBg==".to_string())
+        );
+        assert_eq!(
+            aes_decrypt_with_mode("XID57uH3ajcwUAyL+rrgBg==", "0123456789abcdefXYZ", "mysql"),
+            Ok("MySQL".to_string())
+        );
+    }
+
+    #[test]
+    fn test_aes_mysql_short_key() {
+        // MySQL folds keys of any length, so values encrypted with short keys
+        // must still be readable
+        let encrypted = aes_encrypt_with_mode("MySQL", "key", "mysql").unwrap();
+        assert_eq!(
+            aes_decrypt_with_mode(&encrypted, "key", "mysql"),
+            Ok("MySQL".to_string())
+        );
+        let padded = "key\0\0\0\0\0\0\0\0\0\0\0\0\0";
+        assert_eq!(
+            aes_encrypt_with_mode("MySQL", padded, "mysql"),
+            Ok(encrypted)
+        );
+    }
+
+    #[test]
+    fn test_aes_binary_key() {
+        let key: Vec<u8> = (0..32u8)
+            .map(|i| i.wrapping_mul(7).wrapping_add(200))
+            .collect();
+        assert!(std::str::from_utf8(&key).is_err());
+        let encrypted = aes_encrypt_binary_key("hello", &key).unwrap();
+        assert_eq!(
+            aes_decrypt_binary_key(&encrypted, &key),
+            Ok("hello".to_string())
+        );
+        let encrypted =
+            aes_encrypt_binary_key_with_mode("hello", &key[..16], "aes-128-cbc").unwrap();
+        assert_eq!(
+            aes_decrypt_binary_key_with_mode(&encrypted, &key[..16], "aes-128-cbc"),
+            Ok("hello".to_string())
+        );
+        let encrypted = chacha20poly1305_encrypt_binary_key("hello", &key).unwrap();
+        assert_eq!(
+            chacha20poly1305_decrypt_binary_key(&encrypted, &key),
+            Ok("hello".to_string())
+        );
+        // a utf8 key is the same as its bytes
+        let encrypted = aes_encrypt_default("hello", KEY_256).unwrap();
+        assert_eq!(
+            aes_decrypt_binary_key(&encrypted, KEY_256.as_bytes()),
+            Ok("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn test_aes_default_is_gcm() {
+        let encrypted = aes_encrypt_default("hello", KEY_256).unwrap();
+        // nonce, ciphertext and tag
+        assert_eq!(STANDARD.decode(&encrypted).unwrap().len(), 12 + 5 + 16);
+        assert_eq!(
+            aes_decrypt_with_mode(&encrypted, KEY_256, "aes-256-gcm"),
+            Ok("hello".to_string())
+        );
+        assert_eq!(
+            aes_decrypt_default(&encrypted, KEY_256),
+            Ok("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn test_aes_decrypt_cbc_with_prepended_iv() {
+        // IV 000102..0f followed by the ciphertext, made with openssl
+        assert_eq!(
+            aes_decrypt_with_mode(
+                "AAECAwQFBgcICQoLDA0ODxtgc0nREwhX2x0KnkbhSoI=",
+                "key\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                "aes-128-cbc"
+            ),
+            Ok("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn test_aes_round_trip() {
+        for (mode, key) in MODES {
+            let encrypted = aes_encrypt_with_mode("secret value ✓", key, mode).unwrap();
+            assert_eq!(
+                aes_decrypt_with_mode(&encrypted, key, mode),
+                Ok("secret value ✓".to_string())
+            );
+            let wrong_key = key.replace('0', "1");
+            assert!(aes_decrypt_with_mode(&encrypted, &wrong_key, mode).is_err());
+        }
+        // random IVs and nonces give different ciphertexts for the same input
+        assert_ne!(
+            aes_encrypt_default("a", KEY_256),
+            aes_encrypt_default("a", KEY_256)
+        );
+    }
+
+    #[test]
+    fn test_aes_key_length() {
+        assert_eq!(
+            aes_encrypt_default("a", KEY_128),
+            Err("Key must be 32 bytes for aes-256-gcm, got 16 bytes".to_string())
+        );
+        assert_eq!(
+            aes_encrypt_with_mode("a", "", "aes-128-cbc"),
+            Err("Key must be 16 bytes for aes-128-cbc, got 0 bytes".to_string())
+        );
+        assert!(aes_decrypt_default("AAEC", "").is_err());
+        // bare block modes use 256 bit keys like the default
+        assert!(aes_encrypt_with_mode("a", KEY_128, "gcm").is_err());
+        assert!(aes_encrypt_with_mode("a", KEY_256, "cbc").is_ok());
+    }
+
+    #[test]
+    fn test_aes_errors() {
+        assert_eq!(
+            aes_encrypt_with_mode("a", KEY_256, "aes-512-gcm"),
+            Err(
+                "Mode must be like 'aes-256-gcm', 'aes-128-cbc' or 'mysql', got mode: aes-512-gcm"
+                    .to_string()
+            )
+        );
+        // ECB is only available as the mysql mode
+        assert!(aes_encrypt_with_mode("a", KEY_128, "aes-128-ecb").is_err());
+        assert!(aes_encrypt_with_mode("a", KEY_128, "ctr").is_err());
+        assert_eq!(
+            aes_decrypt_default("not base64!", KEY_256),
+            Err("Invalid base64 ciphertext: not base64!".to_string())
+        );
+        assert_eq!(
+            aes_decrypt_with_mode("AAEC", KEY_256, "gcm"),
+            Err("Decryption failed, wrong key or corrupted ciphertext".to_string())
+        );
+    }
+
+    #[test]
+    fn test_chacha20poly1305() {
+        let encrypted = chacha20poly1305_encrypt("hello", KEY_256).unwrap();
+        assert_eq!(
+            chacha20poly1305_decrypt(&encrypted, KEY_256),
+            Ok("hello".to_string())
+        );
+        assert!(chacha20poly1305_decrypt(&encrypted, &KEY_256.replace('0', "1")).is_err());
+        let mut tampered = STANDARD.decode(&encrypted).unwrap();
+        tampered[12] ^= 1;
+        assert!(chacha20poly1305_decrypt(&STANDARD.encode(tampered), KEY_256).is_err());
+        assert_eq!(
+            chacha20poly1305_encrypt("hello", "key"),
+            Err("Key must be 32 bytes for chacha20poly1305, got 3 bytes".to_string())
+        );
+    }
+}
@@ -0,0 +1,69 @@
+// Adobe style Ascii85 without the <~ ~> delimiters, which are accepted but not
+// required when decoding.
+const FIRST: u8 = b'!';
+const LAST: u8 = b'u';
+
+pub fn encode(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len().div_ceil(4) * 5);
+    for chunk in data.chunks(4) {
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let mut value = u32::from_be_bytes(group);
+        if chunk.len() == 4 && value == 0 {
+            result.push('z');
+            continue;
+        }
+        let mut digits = [0u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = FIRST + (value % 85) as u8;
+            value /= 85;
+        }
+        // a partial group of n bytes is written as its first n + 1 digits
+        for digit in &digits[..chunk.len() + 1] {
+            result.push(*digit as char);
+        }
+    }
+    result
+}
+
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("Invalid ascii85: {}", text);
+    let trimmed = text.trim();
+    let body = trimmed.strip_prefix("<~").unwrap_or(trimmed);
+    let body = body.strip_suffix("~>").unwrap_or(body);
+
+    let mut result = Vec::with_capacity(body.len() / 5 * 4);
+    let mut group = Vec::with_capacity(5);
+    for byte in body.bytes().filter(|b| !b.is_ascii_whitespace()) {
+        match byte {
+            b'z' if group.is_empty() => result.extend_from_slice(&[0; 4]),
+            FIRST..=LAST => {
+                group.push(byte);
+                if group.len() == 5 {
+                    result.extend_from_slice(&decode_group(&group).ok_or_else(invalid)?);
+                    group.clear();
+                }
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    match group.len() {
+        0 => {}
+        1 => return Err(invalid()),
+        len => {
+            // pad with the highest digit so that truncation gives back the bytes
+            group.resize(5, LAST);
+            let bytes = decode_group(&group).ok_or_else(invalid)?;
+            result.extend_from_slice(&bytes[..len - 1]);
+        }
+    }
+    Ok(result)
+}
+
+fn decode_group(group: &[u8]) -> Option<[u8; 4]> {
+    let mut value: u32 = 0;
+    for digit in group {
+        value = value.checked_mul(85)?.checked_add((digit - FIRST) as u32)?;
+    }
+    Some(value.to_be_bytes())
+}
@@ -0,0 +1,62 @@
+// Conversion between UTF-8 and legacy character sets such as GBK, Shift_JIS or
+// windows-1252. Charset names are the WHATWG labels, which map latin1 and
+// iso-8859-1 to windows-1252 as browsers do.
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+
+fn encoding(charset: &str) -> Result<&'static Encoding, String> {
+    Encoding::for_label(charset.trim().as_bytes())
+        .ok_or_else(|| format!("Unsupported charset: {}", charset))
+}
+
+// A byte order mark wins, then valid UTF-8, which includes plain ASCII, and
+// otherwise the legacy charset the bytes look most like.
+pub fn detect(data: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(data) {
+        return encoding;
+    }
+    if std::str::from_utf8(data).is_ok() {
+        return encoding_rs::UTF_8;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(data, true);
+    detector.guess(None, false)
+}
+
+// Malformed sequences are replaced with U+FFFD rather than failing the row.
+pub fn decode(data: &[u8], encoding: &'static Encoding) -> String {
+    let (text, _) = encoding.decode_with_bom_removal(data);
+    text.into_owned()
+}
+
+pub fn to_utf8(data: &[u8], charset: &str) -> Result<String, String> {
+    Ok(decode(data, encoding(charset)?))
+}
+
+pub fn from_utf8(text: &str, charset: &str) -> Result<Vec<u8>, String> {
+    let encoding = encoding(charset)?;
+    // encoding_rs only decodes UTF-16, as the WHATWG encoding standard does
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let units = text.encode_utf16();
+        return Ok(if encoding == UTF_16LE {
+            units.flat_map(u16::to_le_bytes).collect()
+        } else {
+            units.flat_map(u16::to_be_bytes).collect()
+        });
+    }
+    let (bytes, output_encoding, had_errors) = encoding.encode(text);
+    if output_encoding != encoding {
+        return Err(format!(
+            "Converting to {} is not supported",
+            encoding.name()
+        ));
+    }
+    if had_errors {
+        return Err(format!(
+            "Text cannot be represented in {}: {}",
+            encoding.name(),
+            text
+        ));
+    }
+    Ok(bytes.into_owned())
+}
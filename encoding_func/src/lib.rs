@@ -0,0 +1,371 @@
+mod ascii85;
+mod charset;
+
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use data_encoding::{BASE32, BASE32_NOPAD, HEXLOWER, HEXLOWER_PERMISSIVE};
+use gandiva_rust_udf_macro::udf;
+
+// Decoded values returned as utf8 must be valid UTF-8, the *_binary variants
+// return the raw bytes instead.
+fn to_utf8(bytes: Vec<u8>, encoding: &str) -> Result<String, String> {
+    String::from_utf8(bytes).map_err(|_| format!("Decoded {} is not valid UTF-8", encoding))
+}
+
+fn base64_engine(variant: &str) -> Result<GeneralPurpose, String> {
+    let (alphabet, pad) = match variant.to_ascii_lowercase().as_str() {
+        "standard" => (alphabet::STANDARD, true),
+        "standard_no_pad" => (alphabet::STANDARD, false),
+        "url_safe" => (alphabet::URL_SAFE, true),
+        "url_safe_no_pad" => (alphabet::URL_SAFE, false),
+        _ => {
+            return Err(format!(
+                "Variant must be 'standard', 'standard_no_pad', 'url_safe' or 'url_safe_no_pad', got variant: {}",
+                variant
+            ))
+        }
+    };
+    // padding is written as configured but optional when decoding
+    let config = GeneralPurposeConfig::new()
+        .with_encode_padding(pad)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent);
+    Ok(GeneralPurpose::new(&alphabet, config))
+}
+
+fn base64_decode_bytes(text: &str, variant: &str) -> Result<Vec<u8>, String> {
+    base64_engine(variant)?
+        .decode(text.trim())
+        .map_err(|e| format!("Invalid base64: {}", e))
+}
+
+#[udf(name = "base64_encode")]
+pub fn base64_encode_utf8(text: &str) -> String {
+    base64_encode_binary(text.as_bytes())
+}
+
+#[udf(name = "base64_encode")]
+pub fn base64_encode_binary(data: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(data)
+}
+
+#[udf(name = "base64_encode")]
+pub fn base64_encode_utf8_with_variant(text: &str, variant: &str) -> Result<String, String> {
+    base64_encode_binary_with_variant(text.as_bytes(), variant)
+}
+
+// variant is one of standard, standard_no_pad, url_safe or url_safe_no_pad.
+#[udf(name = "base64_encode")]
+pub fn base64_encode_binary_with_variant(data: &[u8], variant: &str) -> Result<String, String> {
+    Ok(base64_engine(variant)?.encode(data))
+}
+
+#[udf(name = "base64_decode")]
+pub fn base64_decode_default(text: &str) -> Result<String, String> {
+    to_utf8(base64_decode_bytes(text, "standard")?, "base64")
+}
+
+#[udf(name = "base64_decode")]
+pub fn base64_decode_with_variant(text: &str, variant: &str) -> Result<String, String> {
+    to_utf8(base64_decode_bytes(text, variant)?, "base64")
+}
+
+#[udf(name = "base64_decode_binary")]
+pub fn base64_decode_binary_default(text: &str) -> Result<Vec<u8>, String> {
+    base64_decode_bytes(text, "standard")
+}
+
+#[udf(name = "base64_decode_binary")]
+pub fn base64_decode_binary_with_variant(text: &str, variant: &str) -> Result<Vec<u8>, String> {
+    base64_decode_bytes(text, variant)
+}
+
+// RFC 4648 base32, decoding accepts lowercase and missing padding.
+fn base32_decode_bytes(text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim().to_ascii_uppercase();
+    let encoding = if text.ends_with('=') {
+        &BASE32
+    } else {
+        &BASE32_NOPAD
+    };
+    encoding
+        .decode(text.as_bytes())
+        .map_err(|e| format!("Invalid base32: {}", e))
+}
+
+#[udf(name = "base32_encode")]
+pub fn base32_encode_utf8(text: &str) -> String {
+    BASE32.encode(text.as_bytes())
+}
+
+#[udf(name = "base32_encode")]
+pub fn base32_encode_binary(data: &[u8]) -> String {
+    BASE32.encode(data)
+}
+
+#[udf]
+pub fn base32_decode(text: &str) -> Result<String, String> {
+    to_utf8(base32_decode_bytes(text)?, "base32")
+}
+
+#[udf]
+pub fn base32_decode_binary(text: &str) -> Result<Vec<u8>, String> {
+    base32_decode_bytes(text)
+}
+
+// Base58 with the Bitcoin alphabet.
+fn base58_decode_bytes(text: &str) -> Result<Vec<u8>, String> {
+    bs58::decode(text.trim())
+        .into_vec()
+        .map_err(|e| format!("Invalid base58: {}", e))
+}
+
+#[udf(name = "base58_encode")]
+pub fn base58_encode_utf8(text: &str) -> String {
+    bs58::encode(text).into_string()
+}
+
+#[udf(name = "base58_encode")]
+pub fn base58_encode_binary(data: &[u8]) -> String {
+    bs58::encode(data).into_string()
+}
+
+#[udf]
+pub fn base58_decode(text: &str) -> Result<String, String> {
+    to_utf8(base58_decode_bytes(text)?, "base58")
+}
+
+#[udf]
+pub fn base58_decode_binary(text: &str) -> Result<Vec<u8>, String> {
+    base58_decode_bytes(text)
+}
+
+// Lowercase hex, decoding accepts either case.
+fn unhex_bytes(text: &str) -> Result<Vec<u8>, String> {
+    HEXLOWER_PERMISSIVE
+        .decode(text.trim().as_bytes())
+        .map_err(|e| format!("Invalid hex: {}", e))
+}
+
+#[udf(name = "hex_encode")]
+pub fn hex_encode_utf8(text: &str) -> String {
+    HEXLOWER.encode(text.as_bytes())
+}
+
+#[udf(name = "hex_encode")]
+pub fn hex_encode_binary(data: &[u8]) -> String {
+    HEXLOWER.encode(data)
+}
+
+#[udf]
+pub fn unhex(text: &str) -> Result<String, String> {
+    to_utf8(unhex_bytes(text)?, "hex")
+}
+
+#[udf]
+pub fn unhex_binary(text: &str) -> Result<Vec<u8>, String> {
+    unhex_bytes(text)
+}
+
+#[udf(name = "ascii85_encode")]
+pub fn ascii85_encode_utf8(text: &str) -> String {
+    ascii85::encode(text.as_bytes())
+}
+
+#[udf(name = "ascii85_encode")]
+pub fn ascii85_encode_binary(data: &[u8]) -> String {
+    ascii85::encode(data)
+}
+
+#[udf]
+pub fn ascii85_decode(text: &str) -> Result<String, String> {
+    to_utf8(ascii85::decode(text)?, "ascii85")
+}
+
+#[udf]
+pub fn ascii85_decode_binary(text: &str) -> Result<Vec<u8>, String> {
+    ascii85::decode(text)
+}
+
+// The most likely charset of the bytes, e.g. UTF-8, GBK, Shift_JIS or
+// windows-1252.
+#[udf]
+pub fn detect_charset(data: &[u8]) -> String {
+    charset::detect(data).name().to_string()
+}
+
+// Converts bytes in the detected charset to UTF-8.
+#[udf(name = "convert_to_utf8")]
+pub fn convert_to_utf8_detected(data: &[u8]) -> String {
+    charset::decode(data, charset::detect(data))
+}
+
+#[udf]
+pub fn convert_to_utf8(data: &[u8], charset: &str) -> Result<String, String> {
+    charset::to_utf8(data, charset)
+}
+
+#[udf]
+pub fn convert_from_utf8(text: &str, charset: &str) -> Result<Vec<u8>, String> {
+    charset::from_utf8(text, charset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode_utf8("hello?"), "aGVsbG8/");
+        assert_eq!(base64_encode_utf8("hi"), "aGk=");
+        assert_eq!(base64_encode_binary(&[0xfb, 0xff]), "+/8=");
+        assert_eq!(
+            base64_encode_binary_with_variant(&[0xfb, 0xff], "url_safe_no_pad"),
+            Ok("-_8".to_string())
+        );
+        assert_eq!(
+            base64_encode_utf8_with_variant("hi", "standard_no_pad"),
+            Ok("aGk".to_string())
+        );
+        assert_eq!(
+            base64_encode_utf8_with_variant("hi", "mime"),
+            Err("Variant must be 'standard', 'standard_no_pad', 'url_safe' or 'url_safe_no_pad', got variant: mime".to_string())
+        );
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        assert_eq!(base64_decode_default("aGVsbG8/"), Ok("hello?".to_string()));
+        assert_eq!(base64_decode_default("aGk"), Ok("hi".to_string()));
+        assert_eq!(base64_decode_default("aGk="), Ok("hi".to_string()));
+        assert_eq!(
+            base64_decode_binary_with_variant("-_8", "url_safe"),
+            Ok(vec![0xfb, 0xff])
+        );
+        assert_eq!(
+            base64_decode_default("-_8"),
+            Err("Invalid base64: Invalid symbol 45, offset 0.".to_string())
+        );
+        assert_eq!(
+            base64_decode_default("+/8="),
+            Err("Decoded base64 is not valid UTF-8".to_string())
+        );
+        assert_eq!(base64_decode_binary_default("+/8="), Ok(vec![0xfb, 0xff]));
+    }
+
+    #[test]
+    fn test_base32() {
+        assert_eq!(base32_encode_utf8("foobar"), "MZXW6YTBOI======");
+        assert_eq!(base32_encode_binary(&[0xff]), "74======");
+        assert_eq!(base32_decode("MZXW6YTBOI======"), Ok("foobar".to_string()));
+        assert_eq!(base32_decode("mzxw6ytboi"), Ok("foobar".to_string()));
+        assert_eq!(base32_decode_binary("74"), Ok(vec![0xff]));
+        assert!(base32_decode("MZXW1").is_err());
+    }
+
+    #[test]
+    fn test_base58() {
+        assert_eq!(base58_encode_utf8("hello world"), "StV1DL6CwTryKyV");
+        assert_eq!(base58_encode_binary(&[0, 0, 1]), "112");
+        assert_eq!(
+            base58_decode("StV1DL6CwTryKyV"),
+            Ok("hello world".to_string())
+        );
+        assert_eq!(base58_decode_binary("112"), Ok(vec![0, 0, 1]));
+        assert!(base58_decode("0OIl").is_err());
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(hex_encode_utf8("Hi!"), "486921");
+        assert_eq!(hex_encode_binary(&[0xde, 0xad]), "dead");
+        assert_eq!(unhex("486921"), Ok("Hi!".to_string()));
+        assert_eq!(unhex_binary("DEad"), Ok(vec![0xde, 0xad]));
+        assert!(unhex("abc").is_err());
+        assert!(unhex("zz").is_err());
+    }
+
+    #[test]
+    fn test_ascii85() {
+        assert_eq!(ascii85_encode_utf8("Man "), "9jqo^");
+        assert_eq!(ascii85_encode_utf8("sure."), "F*2M7/c");
+        assert_eq!(ascii85_encode_binary(&[0, 0, 0, 0, 1]), "z!<");
+        assert_eq!(ascii85_decode("F*2M7/c"), Ok("sure.".to_string()));
+        assert_eq!(ascii85_decode("<~9jqo^~>"), Ok("Man ".to_string()));
+        assert_eq!(ascii85_decode_binary("z!<"), Ok(vec![0, 0, 0, 0, 1]));
+        assert_eq!(
+            ascii85_decode("F*2M7/c~"),
+            Err("Invalid ascii85: F*2M7/c~".to_string())
+        );
+        assert!(ascii85_decode("s8W-\"").is_err());
+        assert!(ascii85_decode("9jqo^F").is_err());
+    }
+
+    #[test]
+    fn test_detect_charset() {
+        assert_eq!(detect_charset(b"plain ascii"), "UTF-8");
+        assert_eq!(detect_charset("caf\u{e9}".as_bytes()), "UTF-8");
+        assert_eq!(detect_charset(&[0xff, 0xfe, b'h', 0]), "UTF-16LE");
+        assert_eq!(
+            detect_charset(b"Le caf\xe9 est tr\xe8s bon, merci beaucoup"),
+            "windows-1252"
+        );
+        let gbk = convert_from_utf8("数据从旧系统导入时经常出现乱码问题", "gbk").unwrap();
+        assert_eq!(detect_charset(&gbk), "GBK");
+        let sjis = convert_from_utf8("データの文字化けを修正してください", "shift_jis").unwrap();
+        assert_eq!(detect_charset(&sjis), "Shift_JIS");
+    }
+
+    #[test]
+    fn test_convert_to_utf8() {
+        assert_eq!(
+            convert_to_utf8(b"caf\xe9", "latin1"),
+            Ok("caf\u{e9}".to_string())
+        );
+        assert_eq!(
+            convert_to_utf8(&[0xc4, 0xe3, 0xba, 0xc3], "GBK"),
+            Ok("你好".to_string())
+        );
+        assert_eq!(
+            convert_to_utf8(&[0x82, 0xa0], "Shift_JIS"),
+            Ok("あ".to_string())
+        );
+        assert_eq!(
+            convert_to_utf8(&[0x81], "shift_jis"),
+            Ok("\u{fffd}".to_string())
+        );
+        assert_eq!(
+            convert_to_utf8_detected(b"Le caf\xe9 est tr\xe8s bon, merci beaucoup"),
+            "Le café est très bon, merci beaucoup"
+        );
+        assert_eq!(
+            convert_to_utf8(b"abc", "ebcdic"),
+            Err("Unsupported charset: ebcdic".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_from_utf8() {
+        assert_eq!(
+            convert_from_utf8("café", "windows-1252"),
+            Ok(b"caf\xe9".to_vec())
+        );
+        assert_eq!(
+            convert_from_utf8("你好", "gbk"),
+            Ok(vec![0xc4, 0xe3, 0xba, 0xc3])
+        );
+        assert_eq!(
+            convert_from_utf8("hé", "utf-16le"),
+            Ok(vec![b'h', 0, 0xe9, 0])
+        );
+        assert_eq!(
+            convert_from_utf8("hé", "utf-16be"),
+            Ok(vec![0, b'h', 0, 0xe9])
+        );
+        assert_eq!(
+            convert_from_utf8("你好", "latin1"),
+            Err("Text cannot be represented in windows-1252: 你好".to_string())
+        );
+    }
+}
@@ -0,0 +1,160 @@
+// Human readable rendering of sizes, quantities, durations and numbers.
+
+const BINARY_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+const DECIMAL_UNITS: [&str; 7] = ["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+const QUANTITY_SUFFIXES: [&str; 7] = ["", "K", "M", "G", "T", "P", "E"];
+
+// One decimal place, without a trailing ".0".
+fn one_decimal(value: f64) -> String {
+    let formatted = format!("{:.1}", value);
+    match formatted.strip_suffix(".0") {
+        Some(whole) => whole.to_string(),
+        None => formatted,
+    }
+}
+
+// Scales value down by base until it fits below base, returning the scaled
+// value rounded to one decimal and the index of the unit.
+fn scale(value: f64, base: f64, units: usize) -> (f64, usize) {
+    let mut value = value;
+    let mut unit = 0;
+    while unit < units - 1 && (value * 10.0).round() / 10.0 >= base {
+        value /= base;
+        unit += 1;
+    }
+    (value, unit)
+}
+
+pub fn format_bytes(bytes: i64, binary: bool) -> String {
+    let (base, units) = if binary {
+        (1024.0, BINARY_UNITS)
+    } else {
+        (1000.0, DECIMAL_UNITS)
+    };
+    let sign = if bytes < 0 { "-" } else { "" };
+    let (value, unit) = scale(bytes.unsigned_abs() as f64, base, units.len());
+    format!("{}{} {}", sign, one_decimal(value), units[unit])
+}
+
+pub fn format_quantity(value: f64) -> String {
+    if !value.is_finite() {
+        return value.to_string();
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    let (scaled, unit) = scale(value.abs(), 1000.0, QUANTITY_SUFFIXES.len());
+    let number = one_decimal(scaled);
+    if unit == 0 {
+        // rounding may leave nothing but a negative zero
+        return if number == "0" {
+            number
+        } else {
+            format!("{}{}", sign, number)
+        };
+    }
+    format!("{}{} {}", sign, number, QUANTITY_SUFFIXES[unit])
+}
+
+pub fn format_duration_secs(secs: i64) -> String {
+    let mut remaining = secs.unsigned_abs();
+    let mut parts = vec![];
+    for (unit, size) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+        let count = remaining / size;
+        remaining %= size;
+        if count > 0 {
+            parts.push(format!("{}{}", count, unit));
+        }
+    }
+    if parts.is_empty() {
+        return "0s".to_string();
+    }
+    let sign = if secs < 0 { "-" } else { "" };
+    format!("{}{}", sign, parts.join(" "))
+}
+
+pub(crate) struct NumberFormat {
+    pub(crate) group_separator: char,
+    pub(crate) decimal_separator: char,
+    // Indian style grouping: the last three digits, then groups of two
+    pub(crate) lakh: bool,
+}
+
+impl NumberFormat {
+    fn new(group_separator: char, decimal_separator: char) -> NumberFormat {
+        NumberFormat {
+            group_separator,
+            decimal_separator,
+            lakh: false,
+        }
+    }
+
+    pub(crate) fn parse(locale: &str) -> Result<NumberFormat, String> {
+        let normalized = locale.trim().to_ascii_lowercase().replace('_', "-");
+        let language = normalized.split('-').next().unwrap_or_default();
+        let format = match (normalized.as_str(), language) {
+            ("de-ch" | "fr-ch" | "it-ch" | "rm-ch", _) => NumberFormat::new('\'', '.'),
+            ("en-in" | "hi-in", _) | (_, "hi") => NumberFormat {
+                lakh: true,
+                ..NumberFormat::new(',', '.')
+            },
+            (_, "" | "c" | "posix" | "en" | "ja" | "zh" | "ko" | "he" | "th") => {
+                NumberFormat::new(',', '.')
+            }
+            (_, "de" | "nl" | "it" | "es" | "pt" | "id" | "tr" | "da" | "el" | "ro") => {
+                NumberFormat::new('.', ',')
+            }
+            // a no-break space, so that numbers are not wrapped across lines
+            (_, "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "no" | "uk" | "hu") => {
+                NumberFormat::new('\u{a0}', ',')
+            }
+            _ => return Err(format!("Unsupported locale: {}", locale)),
+        };
+        Ok(format)
+    }
+
+    fn group(&self, digits: &str) -> String {
+        let mut groups = vec![];
+        let mut rest = digits;
+        let mut size = 3;
+        while rest.len() > size {
+            let (head, tail) = rest.split_at(rest.len() - size);
+            groups.push(tail);
+            rest = head;
+            if self.lakh {
+                size = 2;
+            }
+        }
+        groups.push(rest);
+        groups.reverse();
+        groups.join(&self.group_separator.to_string())
+    }
+}
+
+pub fn format_number(value: f64, decimals: i64, locale: &str) -> Result<String, String> {
+    if !(0..=15).contains(&decimals) {
+        return Err(format!(
+            "Decimals must be between 0 and 15, got decimals: {}",
+            decimals
+        ));
+    }
+    let format = NumberFormat::parse(locale)?;
+    if !value.is_finite() {
+        return Ok(value.to_string());
+    }
+
+    let formatted = format!("{:.*}", decimals as usize, value.abs());
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+    let mut result = String::new();
+    // values rounding to zero are not shown as negative
+    if value < 0.0 && formatted.bytes().any(|b| matches!(b, b'1'..=b'9')) {
+        result.push('-');
+    }
+    result.push_str(&format.group(integer));
+    if let Some(fraction) = fraction {
+        result.push(format.decimal_separator);
+        result.push_str(fraction);
+    }
+    Ok(result)
+}
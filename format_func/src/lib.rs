@@ -1,17 +1,18 @@
+mod human;
+mod parse;
+mod template;
+
 use gandiva_rust_udf_macro::udf;
-use std::collections::HashMap;
-use strfmt::strfmt;
+use template::Value;
 
 #[udf(name = "format")]
 pub fn format_1(template: &str, value1: &str) -> Result<String, String> {
-    let values = &[value1];
-    return format(template, values);
+    format(template, &[Value::Str(value1)])
 }
 
 #[udf(name = "format")]
 pub fn format_2(template: &str, value1: &str, value2: &str) -> Result<String, String> {
-    let values = &[value1, value2];
-    return format(template, values);
+    format(template, &[Value::Str(value1), Value::Str(value2)])
 }
 
 #[udf(name = "format")]
@@ -21,8 +22,10 @@ pub fn format_3(
     value2: &str,
     value3: &str,
 ) -> Result<String, String> {
-    let values = &[value1, value2, value3];
-    return format(template, values);
+    format(
+        template,
+        &[Value::Str(value1), Value::Str(value2), Value::Str(value3)],
+    )
 }
 
 #[udf(name = "format")]
@@ -33,8 +36,15 @@ pub fn format_4(
     value3: &str,
     value4: &str,
 ) -> Result<String, String> {
-    let values = &[value1, value2, value3, value4];
-    return format(template, values);
+    format(
+        template,
+        &[
+            Value::Str(value1),
+            Value::Str(value2),
+            Value::Str(value3),
+            Value::Str(value4),
+        ],
+    )
 }
 
 #[udf(name = "format")]
@@ -46,31 +56,131 @@ pub fn format_5(
     value4: &str,
     value5: &str,
 ) -> Result<String, String> {
-    let values = &[value1, value2, value3, value4, value5];
-    return format(template, values);
-}
-
-pub fn format(template: &str, values: &[&str]) -> Result<String, String> {
-    let mut vars: HashMap<String, &str> = HashMap::default();
-    for (i, value) in values.iter().enumerate() {
-        vars.insert((i + 1).to_string(), value);
-    }
-    match strfmt(template, &vars) {
-        Ok(result) => Ok(result),
-        Err(e) => {
-            let _error_msg_vals = values
-                .iter()
-                .map(|v| v.to_string())
-                .collect::<Vec<_>>()
-                .join(", ");
-            Err(format!(
-                "Formatting with template '{}' caused an error: {:?}",
-                template, e
-            ))
+    format(
+        template,
+        &[
+            Value::Str(value1),
+            Value::Str(value2),
+            Value::Str(value3),
+            Value::Str(value4),
+            Value::Str(value5),
+        ],
+    )
+}
+
+// Generates a format overload for every combination of utf8, int64, float64
+// and bool for one and two values, named after the types, e.g.
+// format_utf8_float64(template: &str, value1: &str, value2: f64). Dates are
+// passed as ISO 8601 utf8 values, e.g. format('{1:%d.%m.%Y}', '2024-03-05').
+macro_rules! format_overloads {
+    ([$($done:tt)*], [$param:ident $($rest:ident)*]) => {
+        format_overloads!([$($done)* ($param, utf8, &str, Str)], [$($rest)*]);
+        format_overloads!([$($done)* ($param, int64, i64, Int)], [$($rest)*]);
+        format_overloads!([$($done)* ($param, float64, f64, Float)], [$($rest)*]);
+        format_overloads!([$($done)* ($param, bool, bool, Bool)], [$($rest)*]);
+    };
+    // utf8 only, which format_1 and format_2 take
+    ([$(($param:ident, utf8, &str, Str))*], []) => {};
+    ([$(($param:ident, $kind:ident, $ty:ty, $variant:ident))*], []) => {
+        paste::paste! {
+            #[udf(name = "format")]
+            pub fn [<format $(_ $kind)*>](
+                template: &str,
+                $($param: $ty),*
+            ) -> Result<String, String> {
+                format(template, &[$(Value::$variant($param)),*])
+            }
         }
+    };
+}
+
+format_overloads!([], [value1]);
+format_overloads!([], [value1 value2]);
+
+pub fn format(template: &str, values: &[Value]) -> Result<String, String> {
+    template::format(template, values).map_err(|e| {
+        format!(
+            "Formatting with template '{}' caused an error: {:?}",
+            template, e
+        )
+    })
+}
+
+// Sizes in binary units (KiB, MiB, ...) by default, e.g. 1536 => "1.5 KiB".
+#[udf(name = "format_bytes")]
+pub fn format_bytes_binary(bytes: i64) -> String {
+    human::format_bytes(bytes, true)
+}
+
+// mode is either "binary" (KiB, MiB, ...) or "decimal" (KB, MB, ...).
+#[udf(name = "format_bytes")]
+pub fn format_bytes_with_mode(bytes: i64, mode: &str) -> Result<String, String> {
+    match mode.to_ascii_lowercase().as_str() {
+        "binary" | "iec" => Ok(human::format_bytes(bytes, true)),
+        "decimal" | "si" => Ok(human::format_bytes(bytes, false)),
+        _ => Err(format!(
+            "Mode must be 'binary' or 'decimal', got mode: {}",
+            mode
+        )),
     }
 }
 
+// e.g. 1234 => "1.2 K", 3400000 => "3.4 M".
+#[udf]
+pub fn format_quantity(value: f64) -> String {
+    human::format_quantity(value)
+}
+
+// e.g. 183840 => "2d 3h 4m".
+#[udf]
+pub fn format_duration_secs(secs: i64) -> String {
+    human::format_duration_secs(secs)
+}
+
+#[udf(name = "format_number")]
+pub fn format_number_default(value: f64, decimals: i64) -> Result<String, String> {
+    human::format_number(value, decimals, "en")
+}
+
+// Thousands and decimal separators follow the locale, e.g. "en", "de" or "fr-CH".
+#[udf(name = "format_number")]
+pub fn format_number_with_locale(
+    value: f64,
+    decimals: i64,
+    locale: &str,
+) -> Result<String, String> {
+    human::format_number(value, decimals, locale)
+}
+
+// e.g. "1.5 GiB" => 1610612736, "10kb" => 10000.
+#[udf]
+pub fn parse_bytes(text: &str) -> Result<i64, String> {
+    parse::parse_bytes(text)
+}
+
+// Durations such as "1h30m" or "2d 3h 4m", in whole seconds.
+#[udf]
+pub fn parse_duration(text: &str) -> Result<i64, String> {
+    parse::parse_duration(text, 1_000_000_000)
+}
+
+#[udf]
+pub fn parse_duration_ms(text: &str) -> Result<i64, String> {
+    parse::parse_duration(text, 1_000_000)
+}
+
+// e.g. "P3DT4H" => 273600.
+#[udf]
+pub fn parse_iso8601_duration(text: &str) -> Result<i64, String> {
+    parse::parse_iso8601_duration(text)
+}
+
+// e.g. ("1.234,56", "de") => 1234.56.
+#[udf]
+pub fn parse_number_locale(text: &str, locale: &str) -> Result<f64, String> {
+    parse::parse_number_locale(text, locale)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +227,327 @@ mod tests {
         let value = result.err().unwrap();
         assert_eq!(value, "Formatting with template 'Hello, {4},{3}!' caused an error: KeyError(\"Invalid key: 4\")");
     }
+
+    #[test]
+    fn test_format_specs() {
+        assert_eq!(format_1("[{1:>6}]", "ab"), Ok("[    ab]".to_string()));
+        assert_eq!(format_1("[{1:*^6}]", "ab"), Ok("[**ab**]".to_string()));
+        assert_eq!(format_1("[{1:.3}]", "abcdef"), Ok("[abc]".to_string()));
+        assert_eq!(format_1("{{{1}}}", "x"), Ok("{x}".to_string()));
+        // numeric specs parse numbers passed as utf8
+        assert_eq!(format_1("{1:,}", "1234567"), Ok("1,234,567".to_string()));
+        assert_eq!(format_1("{1:.2f}", "2.5"), Ok("2.50".to_string()));
+        assert_eq!(
+            format_1("{1:,}", "many"),
+            Err("Formatting with template '{1:,}' caused an error: TypeError(\"Cannot format 'many' as a number\")".to_string())
+        );
+        assert_eq!(
+            format_1("{1:>1q}", "x"),
+            Err("Formatting with template '{1:>1q}' caused an error: Invalid(\"Invalid format spec: >1q\")".to_string())
+        );
+        assert_eq!(
+            format_1("{1", "x"),
+            Err("Formatting with template '{1' caused an error: Invalid(\"Expected '}' before end of string\")".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_int() {
+        assert_eq!(format_int64("{1:>10}", 42), Ok("        42".to_string()));
+        assert_eq!(format_int64("{1:08x}", 255), Ok("000000ff".to_string()));
+        assert_eq!(format_int64("{1:#X}", 255), Ok("0XFF".to_string()));
+        assert_eq!(format_int64("{1:#010b}", 5), Ok("0b00000101".to_string()));
+        assert_eq!(
+            format_int64("{1:,}", -1234567),
+            Ok("-1,234,567".to_string())
+        );
+        assert_eq!(format_int64("{1:+06}", -42), Ok("-00042".to_string()));
+        assert_eq!(format_int64("{1:+}", 42), Ok("+42".to_string()));
+        assert_eq!(format_int64("{1:.1%}", 1), Ok("100.0%".to_string()));
+        assert_eq!(
+            format_int64_int64("{1} of {2:_}", 3, 1000000),
+            Ok("3 of 1_000_000".to_string())
+        );
+        assert_eq!(
+            format_5("{5}{4}{3}{2}{1}", "1", "2", "3", "4", "5"),
+            Ok("54321".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_float() {
+        assert_eq!(format_float64("{1}", 1.5), Ok("1.5".to_string()));
+        assert_eq!(format_float64("{1:.3}", 1.23456), Ok("1.235".to_string()));
+        assert_eq!(
+            format_float64("{1:>10.2f}", -2.5),
+            Ok("     -2.50".to_string())
+        );
+        assert_eq!(
+            format_float64("{1:,.2f}", 1234567.891),
+            Ok("1,234,567.89".to_string())
+        );
+        assert_eq!(
+            format_float64("{1:.2e}", 1500.0),
+            Ok("1.50e+03".to_string())
+        );
+        assert_eq!(format_float64("{1:.1%}", 0.256), Ok("25.6%".to_string()));
+        assert_eq!(
+            format_float64("{1:010.1f}", -3.25),
+            Ok("-0000003.2".to_string())
+        );
+        assert_eq!(
+            format_float64_float64("{1:.1f} / {2:.1f}", 1.0, 2.0),
+            Ok("1.0 / 2.0".to_string())
+        );
+        assert_eq!(
+            format_float64("{1:x}", 1.5),
+            Err("Formatting with template '{1:x}' caused an error: TypeError(\"Unknown format code 'x' for a float\")".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_bool() {
+        assert_eq!(format_bool("{1}", true), Ok("true".to_string()));
+        assert_eq!(
+            format_bool_bool("[{1:<6}|{2:>6}]", true, false),
+            Ok("[true  | false]".to_string())
+        );
+        assert!(format_bool("{1:d}", true).is_err());
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(
+            format_1("{1:%d.%m.%Y}", "2024-03-05"),
+            Ok("05.03.2024".to_string())
+        );
+        assert_eq!(
+            format_1("{1:%b %-d, %Y}", " 2024-03-05 "),
+            Ok("Mar 5, 2024".to_string())
+        );
+        // other specs format the date as text
+        assert_eq!(format_1("{1}", "2024-03-05"), Ok("2024-03-05".to_string()));
+        assert_eq!(
+            format_1("[{1:>12}]", "2024-03-05"),
+            Ok("[  2024-03-05]".to_string())
+        );
+        assert_eq!(
+            format_1("{1:%H:%M}", "2024-03-05"),
+            Err("Formatting with template '{1:%H:%M}' caused an error: Invalid(\"Invalid date format: %H:%M\")".to_string())
+        );
+        assert_eq!(
+            format_1("{1:%Y}", "yesterday"),
+            Err("Formatting with template '{1:%Y}' caused an error: Invalid(\"Invalid format spec: %Y\")".to_string())
+        );
+        assert!(format_int64("{1:%Y}", 19787).is_err());
+    }
+
+    #[test]
+    fn test_format_mixed() {
+        assert_eq!(
+            format_utf8_float64("{1} costs {2:.2f}", "Tea", 3.5),
+            Ok("Tea costs 3.50".to_string())
+        );
+        assert_eq!(
+            format_int64_utf8("{1:,} rows on {2:%d.%m.%Y}", 1234567, "2024-03-05"),
+            Ok("1,234,567 rows on 05.03.2024".to_string())
+        );
+        assert_eq!(
+            format_bool_float64("{1} {2:.1%}", true, 0.5),
+            Ok("true 50.0%".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_percent_fill() {
+        assert_eq!(format_int64("{1:%>6}", 42), Ok("%%%%42".to_string()));
+        assert_eq!(format_1("{1:%^6}", "ab"), Ok("%%ab%%".to_string()));
+        assert_eq!(
+            format_float64("{1:%<8.1%}", 0.5),
+            Ok("50.0%%%%".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_limits() {
+        assert_eq!(format_1("{1:>1024}", "a").map(|s| s.len()), Ok(1024));
+        assert_eq!(
+            format_1("{1:>99999999999}", "a"),
+            Err("Formatting with template '{1:>99999999999}' caused an error: Invalid(\"Width must be at most 1024, got width: 99999999999\")".to_string())
+        );
+        assert_eq!(
+            format_float64("{1:.99999999999f}", 1.0),
+            Err("Formatting with template '{1:.99999999999f}' caused an error: Invalid(\"Precision must be at most 1024, got precision: 99999999999\")".to_string())
+        );
+        assert!(format_int64("{1:1025}", 1).is_err());
+        assert!(format_float64("{1:.99999999999999999999999}", 1.0).is_err());
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes_binary(0), "0 B");
+        assert_eq!(format_bytes_binary(1023), "1023 B");
+        assert_eq!(format_bytes_binary(1536), "1.5 KiB");
+        assert_eq!(format_bytes_binary(1048575), "1 MiB");
+        assert_eq!(format_bytes_binary(-2048), "-2 KiB");
+        assert_eq!(format_bytes_binary(i64::MIN), "-8 EiB");
+        assert_eq!(
+            format_bytes_with_mode(1500000, "decimal"),
+            Ok("1.5 MB".to_string())
+        );
+        assert_eq!(
+            format_bytes_with_mode(1500000, "binary"),
+            Ok("1.4 MiB".to_string())
+        );
+        assert_eq!(
+            format_bytes_with_mode(1, "metric"),
+            Err("Mode must be 'binary' or 'decimal', got mode: metric".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_quantity() {
+        assert_eq!(format_quantity(0.0), "0");
+        assert_eq!(format_quantity(42.26), "42.3");
+        assert_eq!(format_quantity(999.0), "999");
+        assert_eq!(format_quantity(1234.0), "1.2 K");
+        assert_eq!(format_quantity(999999.0), "1 M");
+        assert_eq!(format_quantity(3400000.0), "3.4 M");
+        assert_eq!(format_quantity(-2500000000.0), "-2.5 G");
+        assert_eq!(format_quantity(-0.01), "0");
+    }
+
+    #[test]
+    fn test_format_duration_secs() {
+        assert_eq!(format_duration_secs(0), "0s");
+        assert_eq!(format_duration_secs(59), "59s");
+        assert_eq!(format_duration_secs(3600), "1h");
+        assert_eq!(format_duration_secs(183840), "2d 3h 4m");
+        assert_eq!(format_duration_secs(-90), "-1m 30s");
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(
+            format_number_default(1234567.891, 2),
+            Ok("1,234,567.89".to_string())
+        );
+        assert_eq!(format_number_default(999.5, 0), Ok("1,000".to_string()));
+        assert_eq!(format_number_default(-0.001, 2), Ok("0.00".to_string()));
+        assert_eq!(
+            format_number_default(-1234.5, 1),
+            Ok("-1,234.5".to_string())
+        );
+        assert_eq!(
+            format_number_with_locale(1234.56, 2, "de"),
+            Ok("1.234,56".to_string())
+        );
+        assert_eq!(
+            format_number_with_locale(1234.56, 2, "fr_FR"),
+            Ok("1\u{a0}234,56".to_string())
+        );
+        assert_eq!(
+            format_number_with_locale(1234567.0, 0, "de-CH"),
+            Ok("1'234'567".to_string())
+        );
+        assert_eq!(
+            format_number_with_locale(12345678.0, 0, "en-IN"),
+            Ok("1,23,45,678".to_string())
+        );
+        assert_eq!(
+            format_number_with_locale(1.0, 2, "xx"),
+            Err("Unsupported locale: xx".to_string())
+        );
+        assert_eq!(
+            format_number_default(1.0, 16),
+            Err("Decimals must be between 0 and 15, got decimals: 16".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(parse_bytes("512"), Ok(512));
+        assert_eq!(parse_bytes("1.5 GiB"), Ok(1610612736));
+        assert_eq!(parse_bytes("10kb"), Ok(10000));
+        assert_eq!(parse_bytes("10K"), Ok(10000));
+        assert_eq!(parse_bytes("2Mi"), Ok(2097152));
+        assert_eq!(parse_bytes(" -1 KiB "), Ok(-1024));
+        assert_eq!(
+            parse_bytes("8 EiB"),
+            Err("Byte size out of range: 8 EiB".to_string())
+        );
+        assert_eq!(
+            parse_bytes("1.5 GB/s"),
+            Err("Invalid byte size: 1.5 GB/s".to_string())
+        );
+        assert!(parse_bytes("GiB").is_err());
+        assert!(parse_bytes("").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("1h30m"), Ok(5400));
+        assert_eq!(parse_duration("2d 3h 4m"), Ok(183840));
+        assert_eq!(parse_duration("1.5 hours"), Ok(5400));
+        assert_eq!(parse_duration("90"), Ok(90));
+        assert_eq!(parse_duration("1500ms"), Ok(1));
+        assert_eq!(parse_duration("-1m 30s"), Ok(-90));
+        assert_eq!(parse_duration_ms("1.5s"), Ok(1500));
+        assert_eq!(parse_duration_ms("1m, 250ms"), Ok(60250));
+        assert_eq!(
+            parse_duration("1h30"),
+            Err("Invalid duration: 1h30".to_string())
+        );
+        assert!(parse_duration("5 fortnights").is_err());
+        assert!(parse_duration("").is_err());
+    }
+
+    #[test]
+    fn test_parse_iso8601_duration() {
+        assert_eq!(parse_iso8601_duration("P3DT4H"), Ok(273600));
+        assert_eq!(parse_iso8601_duration("PT1M30.5S"), Ok(90));
+        assert_eq!(parse_iso8601_duration("P1W"), Ok(604800));
+        assert_eq!(parse_iso8601_duration("P0Y0M1D"), Ok(86400));
+        assert_eq!(parse_iso8601_duration("-PT15M"), Ok(-900));
+        assert_eq!(
+            parse_iso8601_duration("P1M"),
+            Err("Years and months have no fixed length in ISO 8601 duration: P1M".to_string())
+        );
+        assert_eq!(
+            parse_iso8601_duration("PT"),
+            Err("Invalid ISO 8601 duration: PT".to_string())
+        );
+        assert!(parse_iso8601_duration("P1H").is_err());
+        assert!(parse_iso8601_duration("3D").is_err());
+    }
+
+    #[test]
+    fn test_parse_number_locale() {
+        assert_eq!(parse_number_locale("1.234,56", "de"), Ok(1234.56));
+        assert_eq!(parse_number_locale("1,234.56", "en"), Ok(1234.56));
+        assert_eq!(parse_number_locale("1 234,5", "fr"), Ok(1234.5));
+        assert_eq!(parse_number_locale("1'234.5", "de-CH"), Ok(1234.5));
+        assert_eq!(parse_number_locale("-42", "de"), Ok(-42.0));
+        assert_eq!(
+            parse_number_locale("1,234.56", "de"),
+            Err("Invalid number for locale de: 1,234.56".to_string())
+        );
+        assert_eq!(parse_number_locale("12,34,567.5", "en-IN"), Ok(1234567.5));
+        assert_eq!(parse_number_locale("1234567", "en"), Ok(1234567.0));
+        assert_eq!(
+            parse_number_locale("1,5", "en"),
+            Err("Invalid number for locale en: 1,5".to_string())
+        );
+        assert_eq!(
+            parse_number_locale("1.5", "de"),
+            Err("Invalid number for locale de: 1.5".to_string())
+        );
+        assert!(parse_number_locale("1,234,56", "en").is_err());
+        assert!(parse_number_locale("1234,567", "en").is_err());
+        assert!(parse_number_locale("1,234,567", "en-IN").is_err());
+        assert!(parse_number_locale(",123", "en").is_err());
+        assert!(parse_number_locale("--1", "en").is_err());
+        assert!(parse_number_locale("abc", "en").is_err());
+        assert!(parse_number_locale("1", "xx").is_err());
+    }
 }
@@ -0,0 +1,211 @@
+// Parsing of human readable sizes, durations and numbers, the reverse of the
+// functions in human.rs.
+use crate::human::NumberFormat;
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+
+// Splits off the leading number, e.g. "1.5 GiB" => ("1.5", " GiB").
+fn split_number(text: &str) -> (&str, &str) {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    text.split_at(end)
+}
+
+fn split_sign(text: &str) -> (bool, &str) {
+    match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    }
+}
+
+// number * multiplier, exact for integers and rounded to the nearest unit otherwise.
+fn scale(number: &str, multiplier: i128) -> Option<i128> {
+    if !number.contains('.') {
+        return number.parse::<i128>().ok()?.checked_mul(multiplier);
+    }
+    let value: f64 = number.parse().ok()?;
+    let scaled = (value * multiplier as f64).round();
+    if scaled >= i128::MAX as f64 {
+        return None;
+    }
+    Some(scaled as i128)
+}
+
+fn to_i64(value: i128, negative: bool) -> Option<i64> {
+    i64::try_from(if negative { -value } else { value }).ok()
+}
+
+// The B is optional and an i marks binary units: "k" and "kb" are 1000 bytes,
+// "ki" and "kib" are 1024 bytes.
+fn byte_unit(unit: &str) -> Option<i128> {
+    let unit = unit.to_ascii_lowercase();
+    let unit = unit.strip_suffix('b').unwrap_or(&unit);
+    if unit.is_empty() {
+        return Some(1);
+    }
+    let (prefix, base) = match unit.strip_suffix('i') {
+        Some(prefix) => (prefix, 1024i128),
+        None => (unit, 1000i128),
+    };
+    let exponent = ["k", "m", "g", "t", "p", "e"]
+        .iter()
+        .position(|p| *p == prefix)?;
+    Some(base.pow(exponent as u32 + 1))
+}
+
+pub fn parse_bytes(text: &str) -> Result<i64, String> {
+    let invalid = || format!("Invalid byte size: {}", text);
+    let (negative, rest) = split_sign(text.trim());
+    let (number, unit) = split_number(rest);
+    let multiplier = byte_unit(unit.trim()).ok_or_else(invalid)?;
+    let bytes = scale(number, multiplier).ok_or_else(invalid)?;
+    to_i64(bytes, negative).ok_or_else(|| format!("Byte size out of range: {}", text))
+}
+
+fn duration_unit(unit: &str) -> Option<i128> {
+    let nanos = match unit.to_ascii_lowercase().as_str() {
+        "ns" | "nanosecond" | "nanoseconds" => 1,
+        "us" | "µs" | "microsecond" | "microseconds" => 1_000,
+        "ms" | "millisecond" | "milliseconds" => 1_000_000,
+        "s" | "sec" | "secs" | "second" | "seconds" => NANOS_PER_SECOND,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60 * NANOS_PER_SECOND,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 3600 * NANOS_PER_SECOND,
+        "d" | "day" | "days" => 86400 * NANOS_PER_SECOND,
+        "w" | "week" | "weeks" => 7 * 86400 * NANOS_PER_SECOND,
+        _ => return None,
+    };
+    Some(nanos)
+}
+
+// Durations such as "1h30m", "2d 3h 4m" or "1.5 hours", in nanoseconds. A bare
+// number is taken as seconds.
+fn parse_duration_nanos(text: &str) -> Result<(bool, i128), String> {
+    let invalid = || format!("Invalid duration: {}", text);
+    let (negative, mut rest) = split_sign(text.trim());
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    if rest.parse::<f64>().is_ok() {
+        return Ok((negative, scale(rest, NANOS_PER_SECOND).ok_or_else(invalid)?));
+    }
+
+    let mut total: i128 = 0;
+    while !rest.is_empty() {
+        let (number, tail) = split_number(rest);
+        let tail = tail.trim_start();
+        let unit_end = tail
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_end);
+        let nanos = duration_unit(unit)
+            .and_then(|multiplier| scale(number, multiplier))
+            .ok_or_else(invalid)?;
+        total = total.checked_add(nanos).ok_or_else(invalid)?;
+        rest = tail.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+    Ok((negative, total))
+}
+
+pub fn parse_duration(text: &str, unit_nanos: i128) -> Result<i64, String> {
+    let (negative, nanos) = parse_duration_nanos(text)?;
+    to_i64(nanos / unit_nanos, negative).ok_or_else(|| format!("Duration out of range: {}", text))
+}
+
+// ISO 8601 durations such as "P3DT4H" or "PT1M30.5S", in whole seconds. Years
+// and months have no fixed length and are only accepted when zero.
+pub fn parse_iso8601_duration(text: &str) -> Result<i64, String> {
+    let invalid = || format!("Invalid ISO 8601 duration: {}", text);
+    let (negative, rest) = split_sign(text.trim());
+    let rest = rest.strip_prefix(['P', 'p']).ok_or_else(invalid)?;
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut total: i128 = 0;
+    let mut in_time = false;
+    let mut rest = rest;
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix(['T', 't']) {
+            if in_time || tail.is_empty() {
+                return Err(invalid());
+            }
+            in_time = true;
+            rest = tail;
+            continue;
+        }
+        let (number, tail) = split_number(rest);
+        let mut chars = tail.chars();
+        let designator = chars.next().ok_or_else(invalid)?.to_ascii_uppercase();
+        let seconds = match (designator, in_time) {
+            ('Y' | 'M', false) => {
+                if number.parse::<f64>().map_err(|_| invalid())? != 0.0 {
+                    return Err(format!(
+                        "Years and months have no fixed length in ISO 8601 duration: {}",
+                        text
+                    ));
+                }
+                0
+            }
+            ('W', false) => 7 * 86400,
+            ('D', false) => 86400,
+            ('H', true) => 3600,
+            ('M', true) => 60,
+            ('S', true) => 1,
+            _ => return Err(invalid()),
+        };
+        let nanos = scale(number, seconds * NANOS_PER_SECOND).ok_or_else(invalid)?;
+        total = total.checked_add(nanos).ok_or_else(invalid)?;
+        rest = chars.as_str();
+    }
+    to_i64(total / NANOS_PER_SECOND, negative)
+        .ok_or_else(|| format!("Duration out of range: {}", text))
+}
+
+// Group separators are optional but must sit at group boundaries: every three
+// digits, or after the last three and then every two digits for lakh grouping.
+fn is_grouped(integer: &str, format: &NumberFormat, is_group: impl Fn(char) -> bool) -> bool {
+    let groups: Vec<&str> = integer.split(is_group).collect();
+    let size = if format.lakh { 2 } else { 3 };
+    let last = groups.len() - 1;
+    groups.iter().enumerate().all(|(i, group)| match i {
+        _ if groups.len() == 1 => true,
+        0 => (1..=size).contains(&group.len()),
+        _ if i == last => group.len() == 3,
+        _ => group.len() == size,
+    })
+}
+
+pub fn parse_number_locale(text: &str, locale: &str) -> Result<f64, String> {
+    let invalid = || format!("Invalid number for locale {}: {}", locale, text);
+    let format = NumberFormat::parse(locale)?;
+    // spaces may stand in for a no-break space
+    let is_group = |c: char| {
+        c == format.group_separator
+            || (format.group_separator == '\u{a0}' && matches!(c, ' ' | '\u{202f}'))
+    };
+    let (negative, rest) = split_sign(text.trim());
+    let (integer, fraction) = match rest.split_once(format.decimal_separator) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (rest, None),
+    };
+    let is_separator = |c: char| is_group(c) || c == '.' || c == ',';
+    if !is_grouped(integer, &format, is_group)
+        || fraction.is_some_and(|fraction| fraction.contains(is_separator))
+    {
+        return Err(invalid());
+    }
+
+    let mut number: String = integer.chars().filter(|c| !is_group(*c)).collect();
+    if number.contains(is_separator) || number.starts_with(['+', '-']) {
+        return Err(invalid());
+    }
+    if let Some(fraction) = fraction {
+        number.push('.');
+        number.push_str(fraction);
+    }
+    match number.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(if negative { -value } else { value }),
+        _ => Err(invalid()),
+    }
+}
@@ -0,0 +1,325 @@
+// Templates with numbered placeholders such as "{1}" or "{2:>10.3}". Format
+// specs follow Python's mini-language, [[fill]align][sign][#][0][width]
+// [grouping][.precision][type], except that a precision without a type means
+// fixed decimals as in Rust. Width and precision are at most MAX_WIDTH. ISO
+// 8601 dates such as "2024-03-05" also take a strftime pattern as their spec,
+// e.g. "{1:%d.%m.%Y}".
+use chrono::NaiveDate;
+use std::fmt::Write;
+
+const MAX_WIDTH: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value<'a> {
+    Str(&'a str),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+// Named like the errors of strfmt, which this replaces, to keep the messages.
+#[derive(Debug, PartialEq)]
+pub enum FmtError {
+    Invalid(String),
+    KeyError(String),
+    TypeError(String),
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<char>,
+    sign: Option<char>,
+    alternate: bool,
+    width: usize,
+    grouping: Option<char>,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+fn take_number(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    name: &str,
+) -> Result<Option<usize>, FmtError> {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    if digits.is_empty() {
+        return Ok(None);
+    }
+    match digits.parse() {
+        Ok(number) if number <= MAX_WIDTH => Ok(Some(number)),
+        _ => Err(FmtError::Invalid(format!(
+            "{} must be at most {}, got {}: {}",
+            name,
+            MAX_WIDTH,
+            name.to_lowercase(),
+            digits
+        ))),
+    }
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Result<Spec, FmtError> {
+        let invalid = || FmtError::Invalid(format!("Invalid format spec: {}", spec));
+        let mut result = Spec::default();
+        let mut chars = spec.chars().peekable();
+        let mut lookahead = spec.chars();
+        match (lookahead.next(), lookahead.next()) {
+            (Some(fill), Some(align @ ('<' | '>' | '^' | '='))) => {
+                result.fill = Some(fill);
+                result.align = Some(align);
+                chars.nth(1);
+            }
+            (Some(align @ ('<' | '>' | '^' | '=')), _) => {
+                result.align = Some(align);
+                chars.next();
+            }
+            _ => {}
+        }
+        result.sign = chars.next_if(|c| matches!(c, '+' | '-' | ' '));
+        result.alternate = chars.next_if_eq(&'#').is_some();
+        if chars.next_if_eq(&'0').is_some() && result.align.is_none() {
+            // zero padding goes between the sign and the digits
+            result.fill = Some('0');
+            result.align = Some('=');
+        }
+        result.width = take_number(&mut chars, "Width")?.unwrap_or(0);
+        result.grouping = chars.next_if(|c| matches!(c, ',' | '_'));
+        if chars.next_if_eq(&'.').is_some() {
+            result.precision = Some(take_number(&mut chars, "Precision")?.ok_or_else(invalid)?);
+        }
+        result.kind = chars.next_if(|c| "sdxXobeEf%".contains(*c));
+        if chars.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(result)
+    }
+
+    fn is_numeric(&self) -> bool {
+        self.grouping.is_some() || matches!(self.kind, Some(kind) if kind != 's')
+    }
+}
+
+// Python style exponent, e.g. 1.50e+03 rather than Rust's 1.50e3.
+fn exponent(value: f64, precision: usize, upper: bool) -> String {
+    let formatted = format!("{:.*e}", precision, value);
+    let (mantissa, exp) = formatted.split_once('e').unwrap();
+    let (exp_sign, exp_digits) = match exp.strip_prefix('-') {
+        Some(digits) => ('-', digits),
+        None => ('+', exp),
+    };
+    let e = if upper { 'E' } else { 'e' };
+    format!("{}{}{}{:0>2}", mantissa, e, exp_sign, exp_digits)
+}
+
+fn group(digits: &str, separator: char, size: usize) -> String {
+    let (integer, rest) = digits.split_at(digits.find('.').unwrap_or(digits.len()));
+    let mut grouped = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % size == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(c);
+    }
+    grouped + rest
+}
+
+fn pad(text: &str, spec: &Spec, default_align: char, sign_len: usize) -> String {
+    let len = text.chars().count();
+    if len >= spec.width {
+        return text.to_string();
+    }
+    let fill = spec.fill.unwrap_or(' ').to_string();
+    let padding = spec.width - len;
+    match spec.align.unwrap_or(default_align) {
+        '<' => format!("{}{}", text, fill.repeat(padding)),
+        '^' => format!(
+            "{}{}{}",
+            fill.repeat(padding / 2),
+            text,
+            fill.repeat(padding - padding / 2)
+        ),
+        '=' => {
+            let (sign, digits) = text.split_at(sign_len);
+            format!("{}{}{}", sign, fill.repeat(padding), digits)
+        }
+        _ => format!("{}{}", fill.repeat(padding), text),
+    }
+}
+
+fn format_str(text: &str, spec: &Spec) -> Result<String, FmtError> {
+    if spec.sign.is_some() || spec.alternate || spec.align == Some('=') {
+        return Err(FmtError::TypeError(
+            "Sign, '#' and '=' alignment are not allowed for strings".to_string(),
+        ));
+    }
+    let text: String = match spec.precision {
+        Some(precision) => text.chars().take(precision).collect(),
+        None => text.to_string(),
+    };
+    Ok(pad(&text, spec, '<', 0))
+}
+
+fn format_int(value: i64, spec: &Spec) -> Result<String, FmtError> {
+    let (radix, prefix) = match spec.kind {
+        None | Some('d') if spec.precision.is_none() => (10, ""),
+        Some('x') => (16, "0x"),
+        Some('X') => (16, "0X"),
+        Some('o') => (8, "0o"),
+        Some('b') => (2, "0b"),
+        _ => return format_float(value as f64, spec),
+    };
+    let magnitude = value.unsigned_abs();
+    let mut digits = match radix {
+        16 => format!("{:x}", magnitude),
+        8 => format!("{:o}", magnitude),
+        2 => format!("{:b}", magnitude),
+        _ => magnitude.to_string(),
+    };
+    if spec.kind == Some('X') {
+        digits = digits.to_uppercase();
+    }
+    if let Some(separator) = spec.grouping {
+        digits = group(&digits, separator, if radix == 10 { 3 } else { 4 });
+    }
+    let prefix = if spec.alternate { prefix } else { "" };
+    Ok(format_signed(value < 0, prefix, &digits, spec))
+}
+
+fn format_float(value: f64, spec: &Spec) -> Result<String, FmtError> {
+    let magnitude = value.abs();
+    let mut digits = match spec.kind {
+        _ if !value.is_finite() => magnitude.to_string(),
+        None if spec.precision.is_none() => magnitude.to_string(),
+        None | Some('f') => format!("{:.*}", spec.precision.unwrap_or(6), magnitude),
+        Some('e') | Some('E') => exponent(
+            magnitude,
+            spec.precision.unwrap_or(6),
+            spec.kind == Some('E'),
+        ),
+        Some('%') => format!("{:.*}%", spec.precision.unwrap_or(6), magnitude * 100.0),
+        Some(kind) => {
+            return Err(FmtError::TypeError(format!(
+                "Unknown format code '{}' for a float",
+                kind
+            )))
+        }
+    };
+    if let Some(separator) = spec.grouping {
+        digits = group(&digits, separator, 3);
+    }
+    Ok(format_signed(value.is_sign_negative(), "", &digits, spec))
+}
+
+fn format_signed(negative: bool, prefix: &str, digits: &str, spec: &Spec) -> String {
+    let sign = match (negative, spec.sign) {
+        (true, _) => "-",
+        (false, Some('+')) => "+",
+        (false, Some(' ')) => " ",
+        _ => "",
+    };
+    let text = format!("{}{}{}", sign, prefix, digits);
+    pad(&text, spec, '>', sign.len() + prefix.len())
+}
+
+fn format_date(date: NaiveDate, pattern: &str) -> Result<String, FmtError> {
+    let mut result = String::new();
+    // fails for invalid patterns and for time fields, which a date does not have
+    write!(result, "{}", date.format(pattern))
+        .map_err(|_| FmtError::Invalid(format!("Invalid date format: {}", pattern)))?;
+    Ok(result)
+}
+
+// Numbers that arrive as strings are parsed when the spec needs a number.
+fn format_text(text: &str, spec: &Spec) -> Result<String, FmtError> {
+    if !spec.is_numeric() {
+        return format_str(text, spec);
+    }
+    let text = text.trim();
+    if let Ok(number) = text.parse::<i64>() {
+        format_int(number, spec)
+    } else if let Ok(number) = text.parse::<f64>() {
+        format_float(number, spec)
+    } else {
+        Err(FmtError::TypeError(format!(
+            "Cannot format '{}' as a number",
+            text
+        )))
+    }
+}
+
+fn format_value(value: Value, spec: &str) -> Result<String, FmtError> {
+    match value {
+        // a spec that is not a format spec may be a strftime pattern for a date
+        Value::Str(text) => match Spec::parse(spec) {
+            Ok(spec) => format_text(text, &spec),
+            Err(e) => match NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d") {
+                Ok(date) if spec.contains('%') => format_date(date, spec),
+                _ => Err(e),
+            },
+        },
+        Value::Bool(value) => {
+            let spec = Spec::parse(spec)?;
+            if spec.is_numeric() {
+                return Err(FmtError::TypeError(
+                    "Cannot format a bool as a number".to_string(),
+                ));
+            }
+            format_str(&value.to_string(), &spec)
+        }
+        Value::Int(value) => {
+            let spec = Spec::parse(spec)?;
+            match spec.kind {
+                Some('s') => format_str(&value.to_string(), &spec),
+                _ => format_int(value, &spec),
+            }
+        }
+        Value::Float(value) => {
+            let spec = Spec::parse(spec)?;
+            match spec.kind {
+                Some('s') => format_str(&value.to_string(), &spec),
+                _ => format_float(value, &spec),
+            }
+        }
+    }
+}
+
+pub fn format(template: &str, values: &[Value]) -> Result<String, FmtError> {
+    let mut result = String::with_capacity(template.len());
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                result.push('}');
+            }
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or_else(|| {
+                    FmtError::Invalid("Expected '}' before end of string".to_string())
+                })?;
+                let (key, spec) = rest[..end].split_once(':').unwrap_or((&rest[..end], ""));
+                let value = key
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| values.get(i.checked_sub(1)?))
+                    .ok_or_else(|| FmtError::KeyError(format!("Invalid key: {}", key)))?;
+                result.push_str(&format_value(*value, spec)?);
+                chars = rest[end + 1..].chars();
+            }
+            '}' => {
+                return Err(FmtError::Invalid(
+                    "Single '}' encountered in format string".to_string(),
+                ))
+            }
+            _ => result.push(c),
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,164 @@
+// CityHash64 as of CityHash 1.0.2, the version ClickHouse's cityHash64 is
+// frozen at. Later releases give different values for longer inputs.
+const K0: u64 = 0xc3a5c85c97cb3127;
+const K1: u64 = 0xb492b66fbe98f273;
+const K2: u64 = 0x9ae16a3b2f90404f;
+const K3: u64 = 0xc949d7c7509e6557;
+const K_MUL: u64 = 0x9ddfea08eb382d69;
+
+fn fetch64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn fetch32(data: &[u8], offset: usize) -> u64 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as u64
+}
+
+fn shift_mix(value: u64) -> u64 {
+    value ^ (value >> 47)
+}
+
+fn hash_len16(u: u64, v: u64) -> u64 {
+    let mut a = (u ^ v).wrapping_mul(K_MUL);
+    a ^= a >> 47;
+    let mut b = (v ^ a).wrapping_mul(K_MUL);
+    b ^= b >> 47;
+    b.wrapping_mul(K_MUL)
+}
+
+fn hash_len0to16(data: &[u8]) -> u64 {
+    let len = data.len();
+    if len > 8 {
+        let a = fetch64(data, 0);
+        let b = fetch64(data, len - 8);
+        return hash_len16(a, b.wrapping_add(len as u64).rotate_right(len as u32)) ^ b;
+    }
+    if len >= 4 {
+        let a = fetch32(data, 0);
+        return hash_len16((len as u64).wrapping_add(a << 3), fetch32(data, len - 4));
+    }
+    if len > 0 {
+        let y = data[0] as u32 + ((data[len >> 1] as u32) << 8);
+        let z = len as u32 + ((data[len - 1] as u32) << 2);
+        return shift_mix((y as u64).wrapping_mul(K2) ^ (z as u64).wrapping_mul(K3))
+            .wrapping_mul(K2);
+    }
+    K2
+}
+
+fn hash_len17to32(data: &[u8]) -> u64 {
+    let len = data.len();
+    let a = fetch64(data, 0).wrapping_mul(K1);
+    let b = fetch64(data, 8);
+    let c = fetch64(data, len - 8).wrapping_mul(K2);
+    let d = fetch64(data, len - 16).wrapping_mul(K0);
+    hash_len16(
+        a.wrapping_sub(b)
+            .rotate_right(43)
+            .wrapping_add(c.rotate_right(30))
+            .wrapping_add(d),
+        a.wrapping_add((b ^ K3).rotate_right(20))
+            .wrapping_sub(c)
+            .wrapping_add(len as u64),
+    )
+}
+
+fn hash_len33to64(data: &[u8]) -> u64 {
+    let len = data.len();
+    let mut z = fetch64(data, 24);
+    let mut a = fetch64(data, 0).wrapping_add(
+        (len as u64)
+            .wrapping_add(fetch64(data, len - 16))
+            .wrapping_mul(K0),
+    );
+    let mut b = a.wrapping_add(z).rotate_right(52);
+    let mut c = a.rotate_right(37);
+    a = a.wrapping_add(fetch64(data, 8));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch64(data, 16));
+    let vf = a.wrapping_add(z);
+    let vs = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+
+    a = fetch64(data, 16).wrapping_add(fetch64(data, len - 32));
+    z = fetch64(data, len - 8);
+    b = a.wrapping_add(z).rotate_right(52);
+    c = a.rotate_right(37);
+    a = a.wrapping_add(fetch64(data, len - 24));
+    c = c.wrapping_add(a.rotate_right(7));
+    a = a.wrapping_add(fetch64(data, len - 16));
+    let wf = a.wrapping_add(z);
+    let ws = b.wrapping_add(a.rotate_right(31)).wrapping_add(c);
+
+    let r = shift_mix(
+        vf.wrapping_add(ws)
+            .wrapping_mul(K2)
+            .wrapping_add(wf.wrapping_add(vs).wrapping_mul(K0)),
+    );
+    shift_mix(r.wrapping_mul(K0).wrapping_add(vs)).wrapping_mul(K2)
+}
+
+fn weak_hash_len32_with_seeds(data: &[u8], offset: usize, a: u64, b: u64) -> (u64, u64) {
+    let w = fetch64(data, offset);
+    let x = fetch64(data, offset + 8);
+    let y = fetch64(data, offset + 16);
+    let z = fetch64(data, offset + 24);
+    let mut a = a.wrapping_add(w);
+    let mut b = b.wrapping_add(a).wrapping_add(z).rotate_right(21);
+    let c = a;
+    a = a.wrapping_add(x).wrapping_add(y);
+    b = b.wrapping_add(a.rotate_right(44));
+    (a.wrapping_add(z), b.wrapping_add(c))
+}
+
+pub fn city_hash64(data: &[u8]) -> u64 {
+    let len = data.len();
+    if len <= 16 {
+        return hash_len0to16(data);
+    }
+    if len <= 32 {
+        return hash_len17to32(data);
+    }
+    if len <= 64 {
+        return hash_len33to64(data);
+    }
+
+    // hash the end first, then loop over 64 byte chunks keeping v, w, x, y and z
+    let mut x = fetch64(data, 0);
+    let mut y = fetch64(data, len - 16) ^ K1;
+    let mut z = fetch64(data, len - 56) ^ K0;
+    let mut v = weak_hash_len32_with_seeds(data, len - 64, len as u64, y);
+    let mut w = weak_hash_len32_with_seeds(data, len - 32, (len as u64).wrapping_mul(K1), K0);
+    z = z.wrapping_add(shift_mix(v.1).wrapping_mul(K1));
+    x = z.wrapping_add(x).rotate_right(39).wrapping_mul(K1);
+    y = y.rotate_right(33).wrapping_mul(K1);
+
+    let mut offset = 0;
+    let mut remaining = (len - 1) & !63;
+    while remaining != 0 {
+        x = x
+            .wrapping_add(y)
+            .wrapping_add(v.0)
+            .wrapping_add(fetch64(data, offset + 16))
+            .rotate_right(37)
+            .wrapping_mul(K1);
+        y = y
+            .wrapping_add(v.1)
+            .wrapping_add(fetch64(data, offset + 48))
+            .rotate_right(42)
+            .wrapping_mul(K1);
+        x ^= w.1;
+        y ^= v.0;
+        z = (z ^ w.0).rotate_right(33);
+        v = weak_hash_len32_with_seeds(data, offset, v.1.wrapping_mul(K1), x.wrapping_add(w.0));
+        w = weak_hash_len32_with_seeds(data, offset + 32, z.wrapping_add(w.1), y);
+        std::mem::swap(&mut z, &mut x);
+        offset += 64;
+        remaining -= 64;
+    }
+    hash_len16(
+        hash_len16(v.0, w.0)
+            .wrapping_add(shift_mix(y).wrapping_mul(K1))
+            .wrapping_add(z),
+        hash_len16(v.1, w.1).wrapping_add(x),
+    )
+}
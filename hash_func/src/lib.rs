@@ -0,0 +1,366 @@
+mod city;
+mod pseudonym;
+
+use gandiva_rust_udf_macro::udf;
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use siphasher::sip::SipHasher24;
+use std::hash::Hasher;
+use std::io::Cursor;
+
+// Cryptographic digests are returned as lowercase hex. The 64 bit hashes are
+// returned as int64 with the same bits, so values at or above 2^63 come out
+// negative; 32 bit hashes and checksums are returned as their unsigned value.
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[udf]
+pub fn md5(text: &str) -> String {
+    hex(&Md5::digest(text.as_bytes()))
+}
+
+#[udf]
+pub fn sha1(text: &str) -> String {
+    hex(&Sha1::digest(text.as_bytes()))
+}
+
+#[udf]
+pub fn sha256(text: &str) -> String {
+    hex(&Sha256::digest(text.as_bytes()))
+}
+
+#[udf]
+pub fn sha512(text: &str) -> String {
+    hex(&Sha512::digest(text.as_bytes()))
+}
+
+#[udf]
+pub fn blake3(text: &str) -> String {
+    blake3::hash(text.as_bytes()).to_hex().to_string()
+}
+
+// Spark's xxhash64 uses a seed of 42, ClickHouse's xxHash64 a seed of 0.
+#[udf(name = "xxhash64")]
+pub fn xxhash64_default(text: &str) -> i64 {
+    xxhash64_with_seed(text, 0)
+}
+
+#[udf(name = "xxhash64")]
+pub fn xxhash64_with_seed(text: &str, seed: i64) -> i64 {
+    xxhash_rust::xxh64::xxh64(text.as_bytes(), seed as u64) as i64
+}
+
+#[udf(name = "xxh3")]
+pub fn xxh3_default(text: &str) -> i64 {
+    xxhash_rust::xxh3::xxh3_64(text.as_bytes()) as i64
+}
+
+#[udf(name = "xxh3")]
+pub fn xxh3_with_seed(text: &str, seed: i64) -> i64 {
+    xxhash_rust::xxh3::xxh3_64_with_seed(text.as_bytes(), seed as u64) as i64
+}
+
+// MurmurHash3_x86_32 as ClickHouse's murmurHash3_32, only the low 32 bits of
+// the seed are used.
+#[udf(name = "murmur3_32")]
+pub fn murmur3_32_default(text: &str) -> i64 {
+    murmur3_32_with_seed(text, 0)
+}
+
+#[udf(name = "murmur3_32")]
+pub fn murmur3_32_with_seed(text: &str, seed: i64) -> i64 {
+    // reading from an in-memory cursor cannot fail
+    murmur3::murmur3_32(&mut Cursor::new(text), seed as u32).unwrap() as i64
+}
+
+// MurmurHash3_x64_128 as hex of the 16 bytes in ClickHouse's murmurHash3_128 order.
+#[udf(name = "murmur3_128")]
+pub fn murmur3_128_default(text: &str) -> String {
+    murmur3_128_with_seed(text, 0)
+}
+
+#[udf(name = "murmur3_128")]
+pub fn murmur3_128_with_seed(text: &str, seed: i64) -> String {
+    let hash = murmur3::murmur3_x64_128(&mut Cursor::new(text), seed as u32).unwrap();
+    hex(&hash.to_le_bytes())
+}
+
+// CityHash v1.0.2, the same values as ClickHouse's cityHash64.
+#[udf]
+pub fn cityhash64(text: &str) -> i64 {
+    city::city_hash64(text.as_bytes()) as i64
+}
+
+#[udf]
+pub fn farmhash64(text: &str) -> i64 {
+    farmhash::hash64(text.as_bytes()) as i64
+}
+
+// FarmHash Fingerprint64, which is guaranteed not to change between releases.
+// Matches BigQuery's FARM_FINGERPRINT and ClickHouse's farmFingerprint64.
+#[udf]
+pub fn farm_fingerprint64(text: &str) -> i64 {
+    farmhash::fingerprint64(text.as_bytes()) as i64
+}
+
+// SipHash-2-4, with a zero key by default as ClickHouse's sipHash64.
+#[udf(name = "siphash")]
+pub fn siphash_default(text: &str) -> i64 {
+    siphash_with_key(text, 0, 0)
+}
+
+#[udf(name = "siphash")]
+pub fn siphash_with_key(text: &str, k0: i64, k1: i64) -> i64 {
+    let mut hasher = SipHasher24::new_with_keys(k0 as u64, k1 as u64);
+    hasher.write(text.as_bytes());
+    hasher.finish() as i64
+}
+
+#[udf]
+pub fn crc32(text: &str) -> i64 {
+    crc32fast::hash(text.as_bytes()) as i64
+}
+
+#[udf]
+pub fn crc32c(text: &str) -> i64 {
+    crc32c::crc32c(text.as_bytes()) as i64
+}
+
+#[udf]
+pub fn hmac_sha256(value: &str, key: &str) -> Result<String, String> {
+    pseudonym::check_key(key)?;
+    Ok(hex(&pseudonym::hmac_sha256(
+        key.as_bytes(),
+        &[value.as_bytes()],
+    )))
+}
+
+// Stable surrogate key for value, format is one of hex, base64 or uuid.
+#[udf]
+pub fn pseudonymize(value: &str, key: &str, format: &str) -> Result<String, String> {
+    pseudonym::pseudonymize(value, key, format)
+}
+
+// Keeps the shape of the address, e.g. "John.Doe@example.com" may become "Kxvq.Pzm@example.com".
+#[udf]
+pub fn tokenize_email(value: &str, key: &str) -> Result<String, String> {
+    pseudonym::tokenize_email(value, key)
+}
+
+// Keeps the formatting, e.g. "+1 (555) 010-0100" may become "+7 (302) 948-1175".
+#[udf]
+pub fn tokenize_phone(value: &str, key: &str) -> Result<String, String> {
+    pseudonym::tokenize_phone(value, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digests() {
+        assert_eq!(md5(""), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(md5("abc"), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(sha1("abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(
+            sha256("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            sha512("abc"),
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+        assert_eq!(
+            blake3(""),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[test]
+    fn test_xxhash() {
+        assert_eq!(xxhash64_default("") as u64, 0xef46db3751d8e999);
+        assert_ne!(xxhash64_with_seed("abc", 42), xxhash64_default("abc"));
+        assert_eq!(xxh3_default("") as u64, 0x2d06800538d394c2);
+        assert_eq!(xxh3_with_seed("abc", 0), xxh3_default("abc"));
+    }
+
+    #[test]
+    fn test_murmur3() {
+        assert_eq!(murmur3_32_default(""), 0);
+        assert_eq!(murmur3_32_default("hello"), 0x248bfa47);
+        assert_eq!(murmur3_32_with_seed("", 1), 0x514e28b7);
+        assert_eq!(murmur3_128_default(""), "00000000000000000000000000000000");
+    }
+
+    #[test]
+    fn test_murmur3_128() {
+        // hex(murmurHash3_128(...)) in ClickHouse, which returns the 16 bytes of
+        // the reference MurmurHash3_x64_128 as written to memory
+        assert_eq!(
+            murmur3_128_default("hello"),
+            "029bbd41b3a7d8cb191dae486a901e5b"
+        );
+        assert_eq!(
+            murmur3_128_default("hello world"),
+            "0e617feb46603f53b163eb607d4697ab"
+        );
+        assert_eq!(
+            murmur3_128_default("The quick brown fox jumps over the lazy dog"),
+            "6c1b07bc7bbc4be347939ac4a93c437a"
+        );
+        // the reference implementation with a seed of 42
+        assert_eq!(
+            murmur3_128_with_seed("ClickHouse", 42),
+            "49333f3ca31d2ef8dd2238e54b164a38"
+        );
+    }
+
+    #[test]
+    fn test_cityhash64() {
+        assert_eq!(cityhash64("") as u64, 11160318154034397263);
+        assert_eq!(cityhash64("hello") as u64, 2578220239953316063);
+        assert_eq!(cityhash64("hello world!") as u64, 9835112831561035123);
+        assert_eq!(
+            cityhash64("The quick brown fox jumps") as u64,
+            1612732933313009427
+        );
+        assert_eq!(
+            cityhash64("The quick brown fox jumps over the lazy dog, twice over") as u64,
+            1882475428265504579
+        );
+        assert_eq!(
+            cityhash64(&"The quick brown fox jumps over the lazy dog. ".repeat(3)[..134]) as u64,
+            2613919034081618888
+        );
+    }
+
+    #[test]
+    fn test_farmhash() {
+        assert_eq!(farm_fingerprint64(""), -7286425919675154353);
+        assert_eq!(farmhash64("hello"), farmhash64("hello"));
+        assert_ne!(farmhash64("hello"), farmhash64("hellp"));
+    }
+
+    #[test]
+    fn test_siphash() {
+        // reference vector for the key 00 01 02 .. 0f
+        assert_eq!(
+            siphash_with_key("", 0x0706050403020100, 0x0f0e0d0c0b0a0908) as u64,
+            0x726fdb47dd0e0e31
+        );
+        assert_eq!(siphash_default("abc"), siphash_with_key("abc", 0, 0));
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32("123456789"), 0xcbf43926);
+        assert_eq!(crc32c("123456789"), 0xe3069283);
+        assert_eq!(crc32(""), 0);
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hmac_sha256("what do ya want for nothing?", "Jefe"),
+            Ok("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843".to_string())
+        );
+        assert_eq!(
+            hmac_sha256("alice", ""),
+            Err("Key must not be empty".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pseudonymize() {
+        let hex = pseudonymize("alice", "secret", "hex").unwrap();
+        assert_eq!(Ok(hex.clone()), hmac_sha256("alice", "secret"));
+        assert_ne!(hex, pseudonymize("alice", "other", "hex").unwrap());
+        assert_eq!(pseudonymize("alice", "secret", "base64").unwrap().len(), 43);
+        let uuid = pseudonymize("alice", "secret", "uuid").unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "8");
+        assert_eq!(&uuid[..8], &hex[..8]);
+        assert_eq!(
+            pseudonymize("alice", "", "hex"),
+            Err("Key must not be empty".to_string())
+        );
+        assert_eq!(
+            pseudonymize("alice", "secret", "int"),
+            Err("Format must be 'hex', 'base64' or 'uuid', got format: int".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tokenize_email() {
+        let token = tokenize_email("John.Doe_42@example.com", "secret").unwrap();
+        assert_eq!(
+            token,
+            tokenize_email("John.Doe_42@example.com", "secret").unwrap()
+        );
+        assert_ne!(
+            token,
+            tokenize_email("John.Doe_42@example.com", "other").unwrap()
+        );
+        assert!(token.ends_with("@example.com"));
+        let local: Vec<char> = token.split('@').next().unwrap().chars().collect();
+        assert_eq!(local.len(), 11);
+        assert!(local[0].is_ascii_uppercase() && local[5].is_ascii_uppercase());
+        assert_eq!((local[4], local[8]), ('.', '_'));
+        assert!(local[9].is_ascii_digit() && local[10].is_ascii_digit());
+        // case only changes the case of the token
+        let lower = tokenize_email("john.doe_42@example.com", "secret").unwrap();
+        assert_eq!(lower.to_lowercase(), token.to_lowercase());
+        // letters outside ASCII are replaced too
+        let token = tokenize_email("Zoë.Müller@example.de", "secret").unwrap();
+        let local: Vec<char> = token.split('@').next().unwrap().chars().collect();
+        assert_eq!(local.len(), 10);
+        assert!(local.iter().all(|c| c.is_ascii()));
+        assert!(local[0].is_ascii_uppercase() && local[4].is_ascii_uppercase());
+        assert!(local[2].is_ascii_lowercase() && local[3] == '.');
+        let upper = tokenize_email("ZOË.MÜLLER@example.de", "secret").unwrap();
+        assert_eq!(upper.to_lowercase(), token.to_lowercase());
+        assert_eq!(
+            tokenize_email("john@example.com", ""),
+            Err("Key must not be empty".to_string())
+        );
+        assert_eq!(
+            tokenize_email("example.com", "secret"),
+            Err("Invalid email address: example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tokenize_phone() {
+        let token = tokenize_phone("+1 (555) 010-0100", "secret").unwrap();
+        assert_eq!(token.len(), 17);
+        assert!(token.starts_with("+"));
+        assert_eq!(&token[2..4], " (");
+        assert_eq!(&token[7..9], ") ");
+        assert_eq!(&token[12..13], "-");
+        let digits: String = token.chars().filter(char::is_ascii_digit).collect();
+        assert_eq!(
+            tokenize_phone("+15550100100", "secret").unwrap(),
+            format!("+{}", digits)
+        );
+        assert_eq!(
+            tokenize_phone("+٩٧١ ٥٠", "secret"),
+            Err("Invalid phone number: +٩٧١ ٥٠".to_string())
+        );
+        assert_eq!(
+            tokenize_phone("555 010½", "secret"),
+            Err("Invalid phone number: 555 010½".to_string())
+        );
+        assert_eq!(
+            tokenize_phone("+1 555", ""),
+            Err("Key must not be empty".to_string())
+        );
+        assert_eq!(
+            tokenize_phone("n/a", "secret"),
+            Err("Invalid phone number: n/a".to_string())
+        );
+    }
+}
@@ -0,0 +1,129 @@
+// Keyed pseudonyms: the same value and key always give the same pseudonym, and
+// without the key a pseudonym cannot be linked back to its value.
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    // HMAC accepts keys of any length, so creating it cannot fail
+    let mut mac = HmacSha256::new_from_slice(key).unwrap();
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+// Without a key anyone could recompute the pseudonyms of guessed values.
+pub fn check_key(key: &str) -> Result<(), String> {
+    if key.is_empty() {
+        return Err("Key must not be empty".to_string());
+    }
+    Ok(())
+}
+
+pub fn pseudonymize(value: &str, key: &str, format: &str) -> Result<String, String> {
+    check_key(key)?;
+    let digest = hmac_sha256(key.as_bytes(), &[value.as_bytes()]);
+    match format.to_ascii_lowercase().as_str() {
+        "hex" => Ok(crate::hex(&digest)),
+        "base64" => Ok(URL_SAFE_NO_PAD.encode(digest)),
+        "uuid" => {
+            let bytes: [u8; 16] = digest[..16].try_into().unwrap();
+            Ok(uuid::Builder::from_custom_bytes(bytes)
+                .into_uuid()
+                .to_string())
+        }
+        _ => Err(format!(
+            "Format must be 'hex', 'base64' or 'uuid', got format: {}",
+            format
+        )),
+    }
+}
+
+// Pseudo-random bytes for value, HMAC-SHA256 in counter mode under a domain
+// label so that emails and phone numbers never share a keystream.
+struct Keystream<'a> {
+    key: &'a [u8],
+    label: &'a [u8],
+    value: &'a [u8],
+    counter: u32,
+    block: [u8; 32],
+    position: usize,
+}
+
+impl<'a> Keystream<'a> {
+    fn new(key: &'a [u8], label: &'a [u8], value: &'a [u8]) -> Keystream<'a> {
+        Keystream {
+            key,
+            label,
+            value,
+            counter: 0,
+            block: [0; 32],
+            position: 32,
+        }
+    }
+
+    // A value below bound, the small modulo bias does not matter for pseudonyms.
+    fn next_below(&mut self, bound: u8) -> u8 {
+        if self.position == self.block.len() {
+            let counter = self.counter.to_be_bytes();
+            self.block = hmac_sha256(self.key, &[self.label, &counter, self.value]);
+            self.counter += 1;
+            self.position = 0;
+        }
+        self.position += 1;
+        self.block[self.position - 1] % bound
+    }
+
+    // Replaces letters and digits of any script by ASCII ones of the same kind
+    // and case, so that no letter of the value is kept.
+    fn replace(&mut self, c: char) -> char {
+        match c {
+            _ if c.is_uppercase() => (b'A' + self.next_below(26)) as char,
+            _ if c.is_alphabetic() => (b'a' + self.next_below(26)) as char,
+            _ if c.is_numeric() => (b'0' + self.next_below(10)) as char,
+            _ => c,
+        }
+    }
+}
+
+// Replaces the local part of an email address, keeping its length, character
+// classes and separators as well as the domain. The token depends on the whole
+// address, ignoring case.
+pub fn tokenize_email(value: &str, key: &str) -> Result<String, String> {
+    check_key(key)?;
+    let (local, domain) = value
+        .rsplit_once('@')
+        .filter(|(local, domain)| !local.is_empty() && !domain.is_empty())
+        .ok_or_else(|| format!("Invalid email address: {}", value))?;
+    let normalized = value.to_lowercase();
+    let mut keystream = Keystream::new(key.as_bytes(), b"email", normalized.as_bytes());
+    let token: String = local.chars().map(|c| keystream.replace(c)).collect();
+    Ok(format!("{}@{}", token, domain))
+}
+
+// Replaces every digit of a phone number, keeping a leading + and all
+// formatting. The token only depends on the digits, so "+1 555-0100" and
+// "+15550100" give the same digits. Only ASCII digits are accepted, other
+// numerals such as '٣' or '½' would be left in the token or change it.
+pub fn tokenize_phone(value: &str, key: &str) -> Result<String, String> {
+    check_key(key)?;
+    let digits: String = value.chars().filter(char::is_ascii_digit).collect();
+    if digits.is_empty() || value.chars().any(|c| c.is_numeric() && !c.is_ascii_digit()) {
+        return Err(format!("Invalid phone number: {}", value));
+    }
+    let mut keystream = Keystream::new(key.as_bytes(), b"phone", digits.as_bytes());
+    Ok(value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_digit() {
+                keystream.replace(c)
+            } else {
+                c
+            }
+        })
+        .collect())
+}
@@ -1,10 +1,106 @@
 use gandiva_rust_udf_macro::udf;
+use std::collections::HashMap;
+use unicode_properties::{EmojiStatus, UnicodeEmoji};
+use unicode_script::{Script, UnicodeScript};
+use unicode_security::MixedScript;
 
 #[udf]
 fn is_ascii(data: &str) -> bool {
     data.is_ascii()
 }
 
+#[udf]
+fn is_valid_utf8(data: &[u8]) -> bool {
+    std::str::from_utf8(data).is_ok()
+}
+
+// Like Python's str methods, the character tests are false for an empty string.
+fn all_chars(data: &str, test: fn(char) -> bool) -> bool {
+    !data.is_empty() && data.chars().all(test)
+}
+
+#[udf]
+fn is_alpha(data: &str) -> bool {
+    all_chars(data, char::is_alphabetic)
+}
+
+#[udf]
+fn is_numeric(data: &str) -> bool {
+    all_chars(data, char::is_numeric)
+}
+
+#[udf]
+fn is_alphanumeric(data: &str) -> bool {
+    all_chars(data, char::is_alphanumeric)
+}
+
+// Control characters are the C0 and C1 ranges, including tabs and newlines.
+#[udf]
+fn has_control_chars(data: &str) -> bool {
+    data.chars().any(char::is_control)
+}
+
+const KEYCAP: char = '\u{20e3}';
+const EMOJI_PRESENTATION_SELECTOR: char = '\u{fe0f}';
+
+// Emoji with emoji presentation, flags, keycaps and text style symbols such as
+// © followed by the emoji presentation selector. Plain digits, # and * don't count.
+#[udf]
+fn has_emoji(data: &str) -> bool {
+    let chars: Vec<char> = data.chars().collect();
+    chars.iter().enumerate().any(|(i, c)| {
+        let next = chars.get(i + 1).copied();
+        match c.emoji_status() {
+            EmojiStatus::EmojiPresentation | EmojiStatus::EmojiPresentationAndModifierBase => true,
+            EmojiStatus::EmojiModifierBase | EmojiStatus::EmojiOther => {
+                next == Some(EMOJI_PRESENTATION_SELECTOR)
+            }
+            EmojiStatus::EmojiOtherAndEmojiComponent => {
+                // keycaps are a digit, # or *, an optional selector and U+20E3
+                let after = if next == Some(EMOJI_PRESENTATION_SELECTOR) {
+                    chars.get(i + 2).copied()
+                } else {
+                    next
+                };
+                after == Some(KEYCAP)
+            }
+            _ => unicode_properties::emoji::is_regional_indicator(*c),
+        }
+    })
+}
+
+// The script most characters are written in, e.g. Latin, Cyrillic or Han.
+// Characters shared between scripts such as digits and punctuation are not
+// counted, and text made of those only is Common.
+#[udf]
+fn dominant_script(data: &str) -> String {
+    let mut counts: HashMap<Script, (usize, usize)> = HashMap::new();
+    for (position, c) in data.chars().enumerate() {
+        let script = c.script();
+        if matches!(script, Script::Common | Script::Inherited | Script::Unknown) {
+            continue;
+        }
+        // ties go to the script seen first
+        counts.entry(script).or_insert((0, position)).0 += 1;
+    }
+    counts
+        .into_iter()
+        .max_by(|(_, (count_a, first_a)), (_, (count_b, first_b))| {
+            count_a.cmp(count_b).then(first_b.cmp(first_a))
+        })
+        .map_or(Script::Common, |(script, _)| script)
+        .full_name()
+        .to_string()
+}
+
+// Whether the text mixes scripts in a way UTS #39 considers suspicious, such
+// as a Cyrillic а in an otherwise Latin domain. Scripts that are commonly
+// written together, like Han and Hiragana in Japanese, are not mixed.
+#[udf]
+fn is_mixed_script(data: &str) -> bool {
+    !data.is_single_script()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -14,4 +110,63 @@ mod tests {
         assert!(is_ascii("hello"));
         assert!(!is_ascii("你好"));
     }
+
+    #[test]
+    fn test_is_valid_utf8() {
+        assert!(is_valid_utf8("你好".as_bytes()));
+        assert!(is_valid_utf8(b""));
+        assert!(!is_valid_utf8(&[0xff, 0xfe]));
+        assert!(!is_valid_utf8(&[0xe4, 0xbd]));
+    }
+
+    #[test]
+    fn test_character_classes() {
+        assert!(is_alpha("héllo"));
+        assert!(is_alpha("你好"));
+        assert!(!is_alpha("hello world"));
+        assert!(!is_alpha(""));
+        assert!(is_numeric("12345"));
+        assert!(is_numeric("٣½"));
+        assert!(!is_numeric("12.5"));
+        assert!(is_alphanumeric("abc123"));
+        assert!(!is_alphanumeric("abc-123"));
+    }
+
+    #[test]
+    fn test_has_control_chars() {
+        assert!(has_control_chars("line\nbreak"));
+        assert!(has_control_chars("bell\u{7}"));
+        assert!(has_control_chars("\u{85}"));
+        assert!(!has_control_chars("plain text"));
+    }
+
+    #[test]
+    fn test_has_emoji() {
+        assert!(has_emoji("nice 👍"));
+        assert!(has_emoji("👍🏽"));
+        assert!(has_emoji("🇩🇪"));
+        assert!(has_emoji("press 1\u{fe0f}\u{20e3}"));
+        assert!(has_emoji("\u{2764}\u{fe0f}"));
+        assert!(!has_emoji("\u{2764}"));
+        assert!(!has_emoji("© 2024 #1 *"));
+        assert!(!has_emoji(""));
+    }
+
+    #[test]
+    fn test_dominant_script() {
+        assert_eq!(dominant_script("hello world"), "Latin");
+        assert_eq!(dominant_script("привет, мир"), "Cyrillic");
+        assert_eq!(dominant_script("你好 world"), "Latin");
+        assert_eq!(dominant_script("東京"), "Han");
+        assert_eq!(dominant_script("ab αβ"), "Latin");
+        assert_eq!(dominant_script("123 !?"), "Common");
+    }
+
+    #[test]
+    fn test_is_mixed_script() {
+        assert!(!is_mixed_script("paypal.com"));
+        assert!(is_mixed_script("pаypal.com"));
+        assert!(!is_mixed_script("東京タワー"));
+        assert!(!is_mixed_script("123"));
+    }
 }
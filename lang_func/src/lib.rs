@@ -0,0 +1,74 @@
+use gandiva_rust_udf_macro::udf;
+use whatlang::Script;
+
+// ISO 639-3 code for text whose language cannot be determined
+const UNDETERMINED: &str = "und";
+
+// The ISO 639-3 code of the language the text is most likely written in, e.g.
+// "eng", "deu" or "cmn", detected offline with trigram models for 69
+// languages. Text without letters is "und".
+#[udf]
+fn detect_language(text: &str) -> String {
+    whatlang::detect_lang(text)
+        .map_or(UNDETERMINED, |lang| lang.code())
+        .to_string()
+}
+
+// Between 0 and 1. Short texts and closely related languages such as Spanish
+// and Portuguese get a low confidence.
+#[udf]
+fn detect_language_confidence(text: &str) -> f64 {
+    whatlang::detect(text).map_or(0.0, |info| info.confidence())
+}
+
+// The writing system of the text, e.g. Latin, Cyrillic or Han, named like
+// dominant_script. Text without letters is Common.
+#[udf]
+fn detect_script(text: &str) -> String {
+    match whatlang::detect_script(text) {
+        Some(Script::Mandarin) => "Han".to_string(),
+        Some(script) => script.name().to_string(),
+        None => "Common".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language("Mein Drucker funktioniert seit gestern nicht mehr"),
+            "deu"
+        );
+        assert_eq!(
+            detect_language("My printer has not been working since yesterday"),
+            "eng"
+        );
+        assert_eq!(
+            detect_language("Здравствуйте, я не могу войти в свой аккаунт уже второй день"),
+            "rus"
+        );
+        assert_eq!(detect_language(""), "und");
+        assert_eq!(detect_language("12345 !?"), "und");
+    }
+
+    #[test]
+    fn test_detect_language_confidence() {
+        let confidence = detect_language_confidence(
+            "Bonjour, je n'arrive plus à me connecter à mon compte depuis hier soir",
+        );
+        assert!(confidence > 0.9 && confidence <= 1.0);
+        assert_eq!(detect_language_confidence(""), 0.0);
+    }
+
+    #[test]
+    fn test_detect_script() {
+        assert_eq!(detect_script("hello world"), "Latin");
+        assert_eq!(detect_script("Привет"), "Cyrillic");
+        assert_eq!(detect_script("你好世界"), "Han");
+        assert_eq!(detect_script("안녕하세요"), "Hangul");
+        assert_eq!(detect_script("123"), "Common");
+    }
+}
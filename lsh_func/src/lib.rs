@@ -0,0 +1,214 @@
+use gandiva_rust_udf_macro::udf;
+
+// Mersenne prime 2^61 - 1, the modulus of the MinHash permutations.
+const MERSENNE_PRIME: u64 = (1 << 61) - 1;
+const MAX_PERMUTATIONS: i64 = 1024;
+// Width of a single MinHash value in the hex encoded signature.
+const HEX_WIDTH: usize = 16;
+
+// FNV-1a followed by the splitmix64 finalizer. Unlike std's hasher it is
+// guaranteed to give the same value on every platform and release.
+fn hash64(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    splitmix64(hash)
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+}
+
+// 64 bit SimHash over the lowercased words of the text, returned as a signed int64.
+// Near-duplicate texts differ in only a few bits, see simhash_distance.
+#[udf]
+pub fn simhash64(text: &str) -> i64 {
+    let mut weights = [0i64; 64];
+    for word in words(text) {
+        let hash = hash64(word.as_bytes());
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash >> bit & 1 == 1 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+    let fingerprint = weights
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| **weight > 0)
+        .fold(0u64, |acc, (bit, _)| acc | 1 << bit);
+    fingerprint as i64
+}
+
+// Number of differing bits between two simhash64 fingerprints.
+#[udf]
+pub fn simhash_distance(a: i64, b: i64) -> i64 {
+    (a ^ b).count_ones() as i64
+}
+
+fn shingles(text: &str, size: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return vec![];
+    }
+    if chars.len() < size {
+        return vec![text.to_string()];
+    }
+    chars.windows(size).map(|w| w.iter().collect()).collect()
+}
+
+// MinHash signature over character shingles, encoded as num_perm 16-digit hex values.
+#[udf]
+pub fn minhash_signature(text: &str, num_perm: i64, shingle: i64) -> Result<String, String> {
+    if !(1..=MAX_PERMUTATIONS).contains(&num_perm) {
+        return Err(format!(
+            "Number of permutations must be between 1 and {}, got num_perm: {}",
+            MAX_PERMUTATIONS, num_perm
+        ));
+    }
+    if shingle < 1 {
+        return Err(format!(
+            "Shingle size must be positive, got shingle: {}",
+            shingle
+        ));
+    }
+
+    let hashes: Vec<u64> = shingles(text, shingle as usize)
+        .iter()
+        .map(|s| hash64(s.as_bytes()) % MERSENNE_PRIME)
+        .collect();
+    let mut signature = String::with_capacity(num_perm as usize * HEX_WIDTH);
+    for i in 0..num_perm as u64 {
+        // the i-th permutation is h(x) = (a * x + b) mod p with a, b derived from i
+        let a = splitmix64(2 * i) % (MERSENNE_PRIME - 1) + 1;
+        let b = splitmix64(2 * i + 1) % MERSENNE_PRIME;
+        let min = hashes
+            .iter()
+            .map(|h| ((a as u128 * *h as u128 + b as u128) % MERSENNE_PRIME as u128) as u64)
+            .min()
+            .unwrap_or(u64::MAX);
+        signature.push_str(&format!("{:016x}", min));
+    }
+    Ok(signature)
+}
+
+fn parse_signature(signature: &str) -> Result<Vec<u64>, String> {
+    let invalid = || format!("Invalid minhash signature: {}", signature);
+    if signature.is_empty() {
+        return Err(invalid());
+    }
+    signature
+        .as_bytes()
+        .chunks(HEX_WIDTH)
+        .map(|chunk| {
+            let value = std::str::from_utf8(chunk).map_err(|_| invalid())?;
+            if value.len() != HEX_WIDTH {
+                return Err(invalid());
+            }
+            u64::from_str_radix(value, 16).map_err(|_| invalid())
+        })
+        .collect()
+}
+
+// Estimated Jaccard similarity of the texts behind two minhash_signature values.
+#[udf]
+pub fn minhash_similarity(sig_a: &str, sig_b: &str) -> Result<f64, String> {
+    let a = parse_signature(sig_a)?;
+    let b = parse_signature(sig_b)?;
+    if a.len() != b.len() {
+        return Err(format!(
+            "Signatures must have the same number of permutations, got {} and {}",
+            a.len(),
+            b.len()
+        ));
+    }
+    let matches = a.iter().zip(&b).filter(|(x, y)| x == y).count();
+    Ok(matches as f64 / a.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simhash64() {
+        assert_eq!(
+            simhash64("The quick brown fox"),
+            simhash64("the QUICK brown fox!")
+        );
+        assert_eq!(simhash64(""), 0);
+        let a = simhash64("the quick brown fox jumps over the lazy dog near the river bank");
+        let b = simhash64("the quick brown fox jumped over the lazy dog near the river bank");
+        let c = simhash64("completely unrelated sentence about stock markets and interest rates");
+        assert!(simhash_distance(a, b) < simhash_distance(a, c));
+    }
+
+    #[test]
+    fn test_simhash_distance() {
+        assert_eq!(simhash_distance(0, 0), 0);
+        assert_eq!(simhash_distance(0, -1), 64);
+        assert_eq!(simhash_distance(0b1010, 0b0110), 2);
+    }
+
+    #[test]
+    fn test_minhash_signature() {
+        let result = minhash_signature("hello world", 4, 3);
+        assert!(result.is_ok());
+        let value = result.unwrap();
+        assert_eq!(value.len(), 64);
+        assert_eq!(minhash_signature("hello world", 4, 3).unwrap(), value);
+    }
+
+    #[test]
+    fn test_minhash_signature_invalid_arguments() {
+        let result = minhash_signature("hello", 0, 3);
+        assert_eq!(
+            result.err().unwrap(),
+            "Number of permutations must be between 1 and 1024, got num_perm: 0"
+        );
+        let result = minhash_signature("hello", 8, 0);
+        assert_eq!(
+            result.err().unwrap(),
+            "Shingle size must be positive, got shingle: 0"
+        );
+    }
+
+    #[test]
+    fn test_minhash_similarity() {
+        let a = minhash_signature("the quick brown fox jumps over the lazy dog", 128, 3).unwrap();
+        let b = minhash_signature("the quick brown fox jumped over the lazy dog", 128, 3).unwrap();
+        let c = minhash_signature("lorem ipsum dolor sit amet", 128, 3).unwrap();
+        assert_eq!(minhash_similarity(&a, &a), Ok(1.0));
+        let similar = minhash_similarity(&a, &b).unwrap();
+        let different = minhash_similarity(&a, &c).unwrap();
+        assert!(similar > 0.6);
+        assert!(different < 0.2);
+    }
+
+    #[test]
+    fn test_minhash_similarity_invalid_signature() {
+        let a = minhash_signature("hello", 2, 3).unwrap();
+        let b = minhash_signature("hello", 4, 3).unwrap();
+        assert_eq!(
+            minhash_similarity(&a, &b).err().unwrap(),
+            "Signatures must have the same number of permutations, got 2 and 4"
+        );
+        assert_eq!(
+            minhash_similarity("xyz", &a).err().unwrap(),
+            "Invalid minhash signature: xyz"
+        );
+    }
+}
@@ -1,13 +1,303 @@
 use gandiva_rust_udf_macro::udf;
 
+fn overflow(x: i64, op: &str, y: i64) -> String {
+    format!("Integer overflow: {} {} {}", x, op, y)
+}
+
+// gcd(i64::MIN, 0) is 2^63, which does not fit in an int64
+#[udf]
+fn gcd(x: i64, y: i64) -> Result<i64, String> {
+    let value = num_integer::gcd(x.unsigned_abs(), y.unsigned_abs());
+    i64::try_from(value).map_err(|_| format!("Integer overflow: gcd({}, {})", x, y))
+}
+
+#[udf]
+fn lcm(x: i64, y: i64) -> Result<i64, String> {
+    let value = num_integer::lcm(x.unsigned_abs() as u128, y.unsigned_abs() as u128);
+    i64::try_from(value).map_err(|_| format!("Integer overflow: lcm({}, {})", x, y))
+}
+
+#[udf]
+fn checked_add(x: i64, y: i64) -> Result<i64, String> {
+    x.checked_add(y).ok_or_else(|| overflow(x, "+", y))
+}
+
+#[udf]
+fn checked_sub(x: i64, y: i64) -> Result<i64, String> {
+    x.checked_sub(y).ok_or_else(|| overflow(x, "-", y))
+}
+
+#[udf]
+fn checked_mul(x: i64, y: i64) -> Result<i64, String> {
+    x.checked_mul(y).ok_or_else(|| overflow(x, "*", y))
+}
+
+#[udf]
+fn saturating_add(x: i64, y: i64) -> i64 {
+    x.saturating_add(y)
+}
+
+#[udf]
+fn saturating_sub(x: i64, y: i64) -> i64 {
+    x.saturating_sub(y)
+}
+
+#[udf]
+fn saturating_mul(x: i64, y: i64) -> i64 {
+    x.saturating_mul(y)
+}
+
+// Integer division truncating toward zero, failing on division by zero and i64::MIN / -1
+#[udf]
+fn safe_div(x: i64, y: i64) -> Result<i64, String> {
+    if y == 0 {
+        return Err(format!("Division by zero: {} / {}", x, y));
+    }
+    x.checked_div(y).ok_or_else(|| overflow(x, "/", y))
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+// Miller-Rabin with the first twelve primes as witnesses is exact for all 64 bit integers
+fn is_prime_u64(n: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for p in WITNESSES {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'witness: for a in WITNESSES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+#[udf]
+fn is_prime(n: i64) -> bool {
+    n > 1 && is_prime_u64(n as u64)
+}
+
+// Smallest prime strictly greater than n
+#[udf]
+fn next_prime(n: i64) -> Result<i64, String> {
+    let mut candidate = n.max(1);
+    loop {
+        candidate = candidate
+            .checked_add(1)
+            .ok_or_else(|| format!("Integer overflow: next_prime({})", n))?;
+        if is_prime(candidate) {
+            return Ok(candidate);
+        }
+    }
+}
+
+#[udf]
+fn mod_pow(base: i64, exp: i64, m: i64) -> Result<i64, String> {
+    if m <= 0 {
+        return Err(format!("Modulus must be positive, got m: {}", m));
+    }
+    if exp < 0 {
+        return Err(format!("Exponent must not be negative, got exp: {}", exp));
+    }
+    let base = base.rem_euclid(m) as u64;
+    Ok(pow_mod(base, exp as u64, m as u64) as i64)
+}
+
+// x such that a * x = 1 (mod m), with 0 <= x < m
+#[udf]
+fn mod_inverse(a: i64, m: i64) -> Result<i64, String> {
+    if m <= 0 {
+        return Err(format!("Modulus must be positive, got m: {}", m));
+    }
+    let (mut old_r, mut r) = (a.rem_euclid(m) as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    if old_r != 1 && m != 1 {
+        return Err(format!("{} has no inverse modulo {}", a, m));
+    }
+    Ok(old_s.rem_euclid(m as i128) as i64)
+}
+
+#[udf]
+fn factorial(n: i64) -> Result<i64, String> {
+    if n < 0 {
+        return Err(format!("Factorial of a negative number: {}", n));
+    }
+    (2..=n).try_fold(1i64, |acc, i| {
+        acc.checked_mul(i)
+            .ok_or_else(|| format!("Integer overflow: factorial({})", n))
+    })
+}
+
+#[udf]
+fn binomial(n: i64, k: i64) -> Result<i64, String> {
+    if n < 0 || k < 0 {
+        return Err(format!(
+            "Binomial coefficient of negative numbers: binomial({}, {})",
+            n, k
+        ));
+    }
+    if k > n {
+        return Ok(0);
+    }
+    // C(n, i) grows with i up to n / 2, so every intermediate value is bounded by the result
+    let r = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..r {
+        result = result * (n - i) as u128 / (i + 1) as u128;
+        if result > i64::MAX as u128 {
+            return Err(format!("Integer overflow: binomial({}, {})", n, k));
+        }
+    }
+    Ok(result as i64)
+}
+
+// Largest integer r such that r * r <= n
+#[udf]
+fn isqrt(n: i64) -> Result<i64, String> {
+    if n < 0 {
+        return Err(format!("Square root of a negative number: {}", n));
+    }
+    let square = |r: i64| r as i128 * r as i128;
+    let mut root = (n as f64).sqrt() as i64;
+    while square(root) > n as i128 {
+        root -= 1;
+    }
+    while square(root + 1) <= n as i128 {
+        root += 1;
+    }
+    Ok(root)
+}
+
+// Largest integer e such that base ^ e <= n
+#[udf]
+fn integer_log(n: i64, base: i64) -> Result<i64, String> {
+    if n <= 0 {
+        return Err(format!("Logarithm of a non-positive number: {}", n));
+    }
+    if base < 2 {
+        return Err(format!(
+            "Logarithm base must be at least 2, got base: {}",
+            base
+        ));
+    }
+    Ok(n.ilog(base) as i64)
+}
+
+// Sum of the decimal digits of |n|
+#[udf]
+fn digit_sum(n: i64) -> i64 {
+    let mut value = n.unsigned_abs();
+    let mut sum = 0;
+    while value > 0 {
+        sum += (value % 10) as i64;
+        value /= 10;
+    }
+    sum
+}
+
+// Bit functions treat the int64 as 64 bits, numbered from 0 at the least significant bit.
+fn bit_position(n: i64) -> Result<u32, String> {
+    if !(0..64).contains(&n) {
+        return Err(format!("Bit position must be between 0 and 63, got: {}", n));
+    }
+    Ok(n as u32)
+}
+
+#[udf]
+fn bit_count(x: i64) -> i64 {
+    x.count_ones() as i64
+}
+
+#[udf]
+fn leading_zeros(x: i64) -> i64 {
+    x.leading_zeros() as i64
+}
+
+#[udf]
+fn trailing_zeros(x: i64) -> i64 {
+    x.trailing_zeros() as i64
+}
+
+#[udf]
+fn bit_test(x: i64, n: i64) -> Result<bool, String> {
+    Ok(x >> bit_position(n)? & 1 == 1)
+}
+
+#[udf]
+fn bit_set(x: i64, n: i64) -> Result<i64, String> {
+    Ok(x | 1 << bit_position(n)?)
+}
+
+#[udf]
+fn bit_clear(x: i64, n: i64) -> Result<i64, String> {
+    Ok(x & !(1 << bit_position(n)?))
+}
+
+// Negative amounts rotate in the other direction
+#[udf]
+fn rotate_left(x: i64, n: i64) -> i64 {
+    x.rotate_left(n.rem_euclid(64) as u32)
+}
+
+#[udf]
+fn rotate_right(x: i64, n: i64) -> i64 {
+    x.rotate_right(n.rem_euclid(64) as u32)
+}
+
 #[udf]
-fn gcd(x: i64, y: i64) -> i64 {
-    num_integer::gcd(x, y)
+fn bit_reverse(x: i64) -> i64 {
+    x.reverse_bits()
 }
 
+// The len bits starting at offset, as an unsigned value
 #[udf]
-fn lcm(x: i64, y: i64) -> i64 {
-    num_integer::lcm(x, y)
+fn bit_slice(x: i64, offset: i64, len: i64) -> Result<i64, String> {
+    let offset = bit_position(offset)?;
+    if len < 0 || len > 64 - offset as i64 {
+        return Err(format!(
+            "Bit slice length must be between 0 and {}, got len: {}",
+            64 - offset,
+            len
+        ));
+    }
+    let shifted = (x as u64) >> offset;
+    let mask = if len == 64 { u64::MAX } else { (1 << len) - 1 };
+    Ok((shifted & mask) as i64)
 }
 
 #[cfg(test)]
@@ -17,12 +307,218 @@ mod tests {
     #[test]
     fn test_gcd() {
         let result = gcd(6, 8);
-        assert_eq!(result, 2);
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn test_gcd_extremes() {
+        assert_eq!(gcd(-6, 8), Ok(2));
+        assert_eq!(gcd(0, 0), Ok(0));
+        assert_eq!(gcd(i64::MIN, 6), Ok(2));
+        assert_eq!(
+            gcd(i64::MIN, 0),
+            Err("Integer overflow: gcd(-9223372036854775808, 0)".to_string())
+        );
     }
 
     #[test]
     fn test_lcm() {
         let result = lcm(6, 8);
-        assert_eq!(result, 24);
+        assert_eq!(result, Ok(24));
+    }
+
+    #[test]
+    fn test_lcm_extremes() {
+        assert_eq!(lcm(-6, 8), Ok(24));
+        assert_eq!(lcm(0, 8), Ok(0));
+        assert_eq!(
+            lcm(4294967311, 4294967357),
+            Err("Integer overflow: lcm(4294967311, 4294967357)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(checked_add(1, 2), Ok(3));
+        assert_eq!(
+            checked_add(i64::MAX, 1),
+            Err("Integer overflow: 9223372036854775807 + 1".to_string())
+        );
+        assert_eq!(checked_sub(1, 2), Ok(-1));
+        assert!(checked_sub(i64::MIN, 1).is_err());
+        assert_eq!(checked_mul(-3, 4), Ok(-12));
+        assert!(checked_mul(i64::MAX, 2).is_err());
+    }
+
+    #[test]
+    fn test_saturating_arithmetic() {
+        assert_eq!(saturating_add(i64::MAX, 1), i64::MAX);
+        assert_eq!(saturating_sub(i64::MIN, 1), i64::MIN);
+        assert_eq!(saturating_mul(i64::MIN, 2), i64::MIN);
+        assert_eq!(saturating_mul(-3, 4), -12);
+    }
+
+    #[test]
+    fn test_safe_div() {
+        assert_eq!(safe_div(7, 2), Ok(3));
+        assert_eq!(safe_div(-7, 2), Ok(-3));
+        assert_eq!(safe_div(1, 0), Err("Division by zero: 1 / 0".to_string()));
+        assert_eq!(
+            safe_div(i64::MIN, -1),
+            Err("Integer overflow: -9223372036854775808 / -1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_is_prime() {
+        assert!(is_prime(2));
+        assert!(is_prime(97));
+        assert!(is_prime(9223372036854775783));
+        assert!(!is_prime(1));
+        assert!(!is_prime(-7));
+        assert!(!is_prime(561));
+        assert!(!is_prime(3215031751));
+    }
+
+    #[test]
+    fn test_next_prime() {
+        assert_eq!(next_prime(-5), Ok(2));
+        assert_eq!(next_prime(2), Ok(3));
+        assert_eq!(next_prime(90), Ok(97));
+        assert_eq!(
+            next_prime(9223372036854775783),
+            Err("Integer overflow: next_prime(9223372036854775783)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(4, 13, 497), Ok(445));
+        assert_eq!(mod_pow(-2, 3, 5), Ok(2));
+        assert_eq!(mod_pow(5, 0, 1), Ok(0));
+        assert_eq!(mod_pow(i64::MAX, i64::MAX, i64::MAX - 1), Ok(1));
+        assert_eq!(
+            mod_pow(2, -1, 5),
+            Err("Exponent must not be negative, got exp: -1".to_string())
+        );
+        assert_eq!(
+            mod_pow(2, 3, 0),
+            Err("Modulus must be positive, got m: 0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Ok(4));
+        assert_eq!(mod_inverse(-3, 11), Ok(7));
+        assert_eq!(mod_inverse(10, 17), Ok(12));
+        assert_eq!(
+            mod_inverse(6, 9),
+            Err("6 has no inverse modulo 9".to_string())
+        );
+    }
+
+    #[test]
+    fn test_factorial() {
+        assert_eq!(factorial(0), Ok(1));
+        assert_eq!(factorial(5), Ok(120));
+        assert_eq!(factorial(20), Ok(2432902008176640000));
+        assert_eq!(
+            factorial(21),
+            Err("Integer overflow: factorial(21)".to_string())
+        );
+        assert!(factorial(-1).is_err());
+    }
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(binomial(5, 2), Ok(10));
+        assert_eq!(binomial(5, 7), Ok(0));
+        assert_eq!(binomial(66, 33), Ok(7219428434016265740));
+        assert!(binomial(68, 34).is_err());
+        assert_eq!(
+            binomial(100, 70),
+            Err("Integer overflow: binomial(100, 70)".to_string())
+        );
+        assert!(binomial(-1, 0).is_err());
+    }
+
+    #[test]
+    fn test_isqrt() {
+        assert_eq!(isqrt(0), Ok(0));
+        assert_eq!(isqrt(15), Ok(3));
+        assert_eq!(isqrt(16), Ok(4));
+        assert_eq!(isqrt(i64::MAX), Ok(3037000499));
+        assert!(isqrt(-1).is_err());
+    }
+
+    #[test]
+    fn test_integer_log() {
+        assert_eq!(integer_log(1000, 10), Ok(3));
+        assert_eq!(integer_log(999, 10), Ok(2));
+        assert_eq!(integer_log(i64::MAX, 2), Ok(62));
+        assert!(integer_log(0, 10).is_err());
+        assert!(integer_log(8, 1).is_err());
+    }
+
+    #[test]
+    fn test_digit_sum() {
+        assert_eq!(digit_sum(12345), 15);
+        assert_eq!(digit_sum(-909), 18);
+        assert_eq!(digit_sum(i64::MIN), 89);
+    }
+
+    #[test]
+    fn test_bit_counts() {
+        assert_eq!(bit_count(0b1011), 3);
+        assert_eq!(bit_count(-1), 64);
+        assert_eq!(leading_zeros(1), 63);
+        assert_eq!(leading_zeros(-1), 0);
+        assert_eq!(trailing_zeros(8), 3);
+        assert_eq!(trailing_zeros(0), 64);
+    }
+
+    #[test]
+    fn test_bit_test_set_clear() {
+        assert_eq!(bit_test(0b100, 2), Ok(true));
+        assert_eq!(bit_test(0b100, 1), Ok(false));
+        assert_eq!(bit_test(i64::MIN, 63), Ok(true));
+        assert_eq!(bit_set(0, 3), Ok(8));
+        assert_eq!(bit_set(0, 63), Ok(i64::MIN));
+        assert_eq!(bit_clear(0b1111, 0), Ok(0b1110));
+        assert_eq!(
+            bit_test(1, 64),
+            Err("Bit position must be between 0 and 63, got: 64".to_string())
+        );
+        assert!(bit_set(1, -1).is_err());
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(rotate_left(1, 1), 2);
+        assert_eq!(rotate_left(i64::MIN, 1), 1);
+        assert_eq!(rotate_right(1, 1), i64::MIN);
+        assert_eq!(rotate_left(1, -1), i64::MIN);
+        assert_eq!(rotate_right(0b10, 65), 1);
+    }
+
+    #[test]
+    fn test_bit_reverse() {
+        assert_eq!(bit_reverse(1), i64::MIN);
+        assert_eq!(bit_reverse(0), 0);
+        assert_eq!(bit_reverse(-1), -1);
+    }
+
+    #[test]
+    fn test_bit_slice() {
+        assert_eq!(bit_slice(0xABCD, 4, 8), Ok(0xBC));
+        assert_eq!(bit_slice(-1, 60, 4), Ok(0xF));
+        assert_eq!(bit_slice(-1, 0, 64), Ok(-1));
+        assert_eq!(bit_slice(0xFF, 0, 0), Ok(0));
+        assert_eq!(
+            bit_slice(1, 60, 5),
+            Err("Bit slice length must be between 0 and 4, got len: 5".to_string())
+        );
+        assert!(bit_slice(1, 64, 0).is_err());
     }
 }
@@ -0,0 +1,695 @@
+// Port of Lawrence Philips' Double Metaphone, following the rule order of the
+// Apache Commons Codec implementation so keys match what other systems produce.
+
+const VOWELS: &str = "AEIOUY";
+const SILENT_START: [&str; 5] = ["GN", "KN", "PN", "WR", "PS"];
+const L_R_N_M_B_H_F_V_W_SPACE: [&str; 10] = ["L", "R", "N", "M", "B", "H", "F", "V", "W", " "];
+const ES_EP_EB_EL_EY_IB_IL_IN_IE_EI_ER: [&str; 11] = [
+    "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+];
+const L_T_K_S_N_M_B_Z: [&str; 8] = ["L", "T", "K", "S", "N", "M", "B", "Z"];
+
+struct DoubleMetaphoneResult {
+    primary: String,
+    alternate: String,
+    max_length: usize,
+}
+
+impl DoubleMetaphoneResult {
+    fn new(max_length: usize) -> Self {
+        DoubleMetaphoneResult {
+            primary: String::new(),
+            alternate: String::new(),
+            max_length,
+        }
+    }
+
+    fn append(&mut self, value: &str) {
+        self.append_primary(value);
+        self.append_alternate(value);
+    }
+
+    fn append_both(&mut self, primary: &str, alternate: &str) {
+        self.append_primary(primary);
+        self.append_alternate(alternate);
+    }
+
+    fn append_primary(&mut self, value: &str) {
+        let remaining = self.max_length.saturating_sub(self.primary.len());
+        self.primary.extend(value.chars().take(remaining));
+    }
+
+    fn append_alternate(&mut self, value: &str) {
+        let remaining = self.max_length.saturating_sub(self.alternate.len());
+        self.alternate.extend(value.chars().take(remaining));
+    }
+
+    fn is_complete(&self) -> bool {
+        self.primary.len() >= self.max_length && self.alternate.len() >= self.max_length
+    }
+}
+
+struct Word {
+    chars: Vec<char>,
+}
+
+impl Word {
+    fn len(&self) -> i64 {
+        self.chars.len() as i64
+    }
+
+    fn char_at(&self, index: i64) -> char {
+        if index < 0 || index >= self.len() {
+            '\0'
+        } else {
+            self.chars[index as usize]
+        }
+    }
+
+    fn contains(&self, start: i64, length: i64, criteria: &[&str]) -> bool {
+        if start < 0 || start + length > self.len() {
+            return false;
+        }
+        let target = &self.chars[start as usize..(start + length) as usize];
+        criteria.iter().any(|c| {
+            c.chars().count() == target.len() && c.chars().zip(target).all(|(a, b)| a == *b)
+        })
+    }
+
+    fn find(&self, needle: &str) -> bool {
+        let length = needle.chars().count() as i64;
+        (0..self.len()).any(|i| self.contains(i, length, &[needle]))
+    }
+}
+
+fn is_vowel(c: char) -> bool {
+    VOWELS.contains(c)
+}
+
+pub fn double_metaphone(value: &str, max_length: usize, alternate: bool) -> String {
+    let cleaned = value.trim().to_uppercase();
+    if cleaned.is_empty() {
+        return String::new();
+    }
+    let value = Word {
+        chars: cleaned.chars().collect(),
+    };
+    let slavo_germanic =
+        value.find("W") || value.find("K") || value.find("CZ") || value.find("WITZ");
+    let mut index: i64 = if SILENT_START.iter().any(|s| cleaned.starts_with(s)) {
+        1
+    } else {
+        0
+    };
+    let mut result = DoubleMetaphoneResult::new(max_length);
+
+    while !result.is_complete() && index < value.len() {
+        index = match value.char_at(index) {
+            'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                if index == 0 {
+                    result.append("A");
+                }
+                index + 1
+            }
+            'B' => {
+                result.append("P");
+                skip_double(&value, index, 'B')
+            }
+            'Ç' => {
+                result.append("S");
+                index + 1
+            }
+            'C' => handle_c(&value, &mut result, index),
+            'D' => handle_d(&value, &mut result, index),
+            'F' => {
+                result.append("F");
+                skip_double(&value, index, 'F')
+            }
+            'G' => handle_g(&value, &mut result, index, slavo_germanic),
+            'H' => handle_h(&value, &mut result, index),
+            'J' => handle_j(&value, &mut result, index, slavo_germanic),
+            'K' => {
+                result.append("K");
+                skip_double(&value, index, 'K')
+            }
+            'L' => handle_l(&value, &mut result, index),
+            'M' => {
+                result.append("M");
+                if condition_m0(&value, index) {
+                    index + 2
+                } else {
+                    index + 1
+                }
+            }
+            'N' => {
+                result.append("N");
+                skip_double(&value, index, 'N')
+            }
+            'Ñ' => {
+                result.append("N");
+                index + 1
+            }
+            'P' => handle_p(&value, &mut result, index),
+            'Q' => {
+                result.append("K");
+                skip_double(&value, index, 'Q')
+            }
+            'R' => handle_r(&value, &mut result, index, slavo_germanic),
+            'S' => handle_s(&value, &mut result, index, slavo_germanic),
+            'T' => handle_t(&value, &mut result, index),
+            'V' => {
+                result.append("F");
+                skip_double(&value, index, 'V')
+            }
+            'W' => handle_w(&value, &mut result, index),
+            'X' => handle_x(&value, &mut result, index),
+            'Z' => handle_z(&value, &mut result, index, slavo_germanic),
+            _ => index + 1,
+        };
+    }
+
+    if alternate {
+        result.alternate
+    } else {
+        result.primary
+    }
+}
+
+fn skip_double(value: &Word, index: i64, c: char) -> i64 {
+    if value.char_at(index + 1) == c {
+        index + 2
+    } else {
+        index + 1
+    }
+}
+
+fn handle_c(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if condition_c0(value, index) {
+        result.append("K");
+        index + 2
+    } else if index == 0 && value.contains(index, 6, &["CAESAR"]) {
+        result.append("S");
+        index + 2
+    } else if value.contains(index, 2, &["CH"]) {
+        handle_ch(value, result, index)
+    } else if value.contains(index, 2, &["CZ"]) && !value.contains(index - 2, 4, &["WICZ"]) {
+        // "Czerny"
+        result.append_both("S", "X");
+        index + 2
+    } else if value.contains(index + 1, 3, &["CIA"]) {
+        // "focaccia"
+        result.append("X");
+        index + 3
+    } else if value.contains(index, 2, &["CC"]) && !(index == 1 && value.char_at(0) == 'M') {
+        // double "cc" but not "McClelland"
+        handle_cc(value, result, index)
+    } else if value.contains(index, 2, &["CK", "CG", "CQ"]) {
+        result.append("K");
+        index + 2
+    } else if value.contains(index, 2, &["CI", "CE", "CY"]) {
+        // Italian vs. English
+        if value.contains(index, 3, &["CIO", "CIE", "CIA"]) {
+            result.append_both("S", "X");
+        } else {
+            result.append("S");
+        }
+        index + 2
+    } else {
+        result.append("K");
+        if value.contains(index + 1, 2, &[" C", " Q", " G"]) {
+            // "Mac Caffrey", "Mac Gregor"
+            index + 3
+        } else if value.contains(index + 1, 1, &["C", "K", "Q"])
+            && !value.contains(index + 1, 2, &["CE", "CI"])
+        {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+fn handle_cc(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if value.contains(index + 2, 1, &["I", "E", "H"]) && !value.contains(index + 2, 2, &["HU"]) {
+        // "bellocchio" but not "bacchus"
+        if (index == 1 && value.char_at(index - 1) == 'A')
+            || value.contains(index - 1, 5, &["UCCEE", "UCCES"])
+        {
+            // "accident", "accede", "succeed"
+            result.append("KS");
+        } else {
+            // "bacci", "bertucci", other Italian
+            result.append("X");
+        }
+        index + 3
+    } else {
+        // Pierce's rule
+        result.append("K");
+        index + 2
+    }
+}
+
+fn handle_ch(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if index > 0 && value.contains(index, 4, &["CHAE"]) {
+        // "Michael"
+        result.append_both("K", "X");
+    } else if condition_ch0(value, index) || condition_ch1(value, index) {
+        // Greek roots ("chemistry", "chorus") or Germanic 'ch' for 'kh' sound
+        result.append("K");
+    } else if index > 0 {
+        if value.contains(0, 2, &["MC"]) {
+            result.append("K");
+        } else {
+            result.append_both("X", "K");
+        }
+    } else {
+        result.append("X");
+    }
+    index + 2
+}
+
+fn handle_d(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if value.contains(index, 2, &["DG"]) {
+        if value.contains(index + 2, 1, &["I", "E", "Y"]) {
+            // "edge"
+            result.append("J");
+            index + 3
+        } else {
+            // "Edgar"
+            result.append("TK");
+            index + 2
+        }
+    } else if value.contains(index, 2, &["DT", "DD"]) {
+        result.append("T");
+        index + 2
+    } else {
+        result.append("T");
+        index + 1
+    }
+}
+
+fn handle_g(
+    value: &Word,
+    result: &mut DoubleMetaphoneResult,
+    index: i64,
+    slavo_germanic: bool,
+) -> i64 {
+    if value.char_at(index + 1) == 'H' {
+        handle_gh(value, result, index)
+    } else if value.char_at(index + 1) == 'N' {
+        if index == 1 && is_vowel(value.char_at(0)) && !slavo_germanic {
+            result.append_both("KN", "N");
+        } else if !value.contains(index + 2, 2, &["EY"])
+            && value.char_at(index + 1) != 'Y'
+            && !slavo_germanic
+        {
+            result.append_both("N", "KN");
+        } else {
+            result.append("KN");
+        }
+        index + 2
+    } else if value.contains(index + 1, 2, &["LI"]) && !slavo_germanic {
+        result.append_both("KL", "L");
+        index + 2
+    } else if index == 0
+        && (value.char_at(index + 1) == 'Y'
+            || value.contains(index + 1, 2, &ES_EP_EB_EL_EY_IB_IL_IN_IE_EI_ER))
+    {
+        // -ges-, -gep-, -gel-, -gie- at beginning
+        result.append_both("K", "J");
+        index + 2
+    } else if (value.contains(index + 1, 2, &["ER"]) || value.char_at(index + 1) == 'Y')
+        && !value.contains(0, 6, &["DANGER", "RANGER", "MANGER"])
+        && !value.contains(index - 1, 1, &["E", "I"])
+        && !value.contains(index - 1, 3, &["RGY", "OGY"])
+    {
+        // -ger-, -gy-
+        result.append_both("K", "J");
+        index + 2
+    } else if value.contains(index + 1, 1, &["E", "I", "Y"])
+        || value.contains(index - 1, 4, &["AGGI", "OGGI"])
+    {
+        // Italian "biaggi"
+        if value.contains(0, 4, &["VAN ", "VON "])
+            || value.contains(0, 3, &["SCH"])
+            || value.contains(index + 1, 2, &["ET"])
+        {
+            // obvious Germanic
+            result.append("K");
+        } else if value.contains(index + 1, 3, &["IER"]) {
+            result.append("J");
+        } else {
+            result.append_both("J", "K");
+        }
+        index + 2
+    } else if value.char_at(index + 1) == 'G' {
+        result.append("K");
+        index + 2
+    } else {
+        result.append("K");
+        index + 1
+    }
+}
+
+fn handle_gh(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if index > 0 && !is_vowel(value.char_at(index - 1)) {
+        result.append("K");
+    } else if index == 0 {
+        if value.char_at(index + 2) == 'I' {
+            result.append("J");
+        } else {
+            result.append("K");
+        }
+    } else if (index > 1 && value.contains(index - 2, 1, &["B", "H", "D"]))
+        || (index > 2 && value.contains(index - 3, 1, &["B", "H", "D"]))
+        || (index > 3 && value.contains(index - 4, 1, &["B", "H"]))
+    {
+        // Parker's rule, e.g. "hugh"
+    } else if index > 2
+        && value.char_at(index - 1) == 'U'
+        && value.contains(index - 3, 1, &["C", "G", "L", "R", "T"])
+    {
+        // "laugh", "McLaughlin", "cough", "gough", "rough", "tough"
+        result.append("F");
+    } else if index > 0 && value.char_at(index - 1) != 'I' {
+        result.append("K");
+    }
+    index + 2
+}
+
+fn handle_h(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    // only keep if first & before vowel or between 2 vowels
+    if (index == 0 || is_vowel(value.char_at(index - 1))) && is_vowel(value.char_at(index + 1)) {
+        result.append("H");
+        index + 2
+    } else {
+        index + 1
+    }
+}
+
+fn handle_j(
+    value: &Word,
+    result: &mut DoubleMetaphoneResult,
+    index: i64,
+    slavo_germanic: bool,
+) -> i64 {
+    if value.contains(index, 4, &["JOSE"]) || value.contains(0, 4, &["SAN "]) {
+        // obvious Spanish, "Jose", "San Jacinto"
+        if (index == 0 && value.char_at(index + 4) == ' ')
+            || value.len() == 4
+            || value.contains(0, 4, &["SAN "])
+        {
+            result.append("H");
+        } else {
+            result.append_both("J", "H");
+        }
+        return index + 1;
+    }
+
+    if index == 0 && !value.contains(index, 4, &["JOSE"]) {
+        result.append_both("J", "A");
+    } else if is_vowel(value.char_at(index - 1))
+        && !slavo_germanic
+        && (value.char_at(index + 1) == 'A' || value.char_at(index + 1) == 'O')
+    {
+        result.append_both("J", "H");
+    } else if index == value.len() - 1 {
+        result.append_both("J", " ");
+    } else if !value.contains(index + 1, 1, &L_T_K_S_N_M_B_Z)
+        && !value.contains(index - 1, 1, &["S", "K", "L"])
+    {
+        result.append("J");
+    }
+    skip_double(value, index, 'J')
+}
+
+fn handle_l(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if value.char_at(index + 1) == 'L' {
+        if condition_l0(value, index) {
+            result.append_primary("L");
+        } else {
+            result.append("L");
+        }
+        index + 2
+    } else {
+        result.append("L");
+        index + 1
+    }
+}
+
+fn handle_p(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if value.char_at(index + 1) == 'H' {
+        result.append("F");
+        index + 2
+    } else {
+        result.append("P");
+        if value.contains(index + 1, 1, &["P", "B"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+fn handle_r(
+    value: &Word,
+    result: &mut DoubleMetaphoneResult,
+    index: i64,
+    slavo_germanic: bool,
+) -> i64 {
+    if index == value.len() - 1
+        && !slavo_germanic
+        && value.contains(index - 2, 2, &["IE"])
+        && !value.contains(index - 4, 2, &["ME", "MA"])
+    {
+        // French, e.g. "Rogier"
+        result.append_alternate("R");
+    } else {
+        result.append("R");
+    }
+    skip_double(value, index, 'R')
+}
+
+fn handle_s(
+    value: &Word,
+    result: &mut DoubleMetaphoneResult,
+    index: i64,
+    slavo_germanic: bool,
+) -> i64 {
+    if value.contains(index - 1, 3, &["ISL", "YSL"]) {
+        // "island", "isle", "carlisle", "carlysle"
+        index + 1
+    } else if index == 0 && value.contains(index, 5, &["SUGAR"]) {
+        result.append_both("X", "S");
+        index + 1
+    } else if value.contains(index, 2, &["SH"]) {
+        if value.contains(index + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+            // Germanic
+            result.append("S");
+        } else {
+            result.append("X");
+        }
+        index + 2
+    } else if value.contains(index, 3, &["SIO", "SIA"]) || value.contains(index, 4, &["SIAN"]) {
+        // Italian and Armenian
+        if slavo_germanic {
+            result.append("S");
+        } else {
+            result.append_both("S", "X");
+        }
+        index + 3
+    } else if (index == 0 && value.contains(index + 1, 1, &["M", "N", "L", "W"]))
+        || value.contains(index + 1, 1, &["Z"])
+    {
+        // "smith" matches "schmidt", "snider" matches "schneider", Slavic -sz-
+        result.append_both("S", "X");
+        if value.contains(index + 1, 1, &["Z"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    } else if value.contains(index, 2, &["SC"]) {
+        handle_sc(value, result, index)
+    } else {
+        if index == value.len() - 1 && value.contains(index - 2, 2, &["AI", "OI"]) {
+            // French, e.g. "resnais", "artois"
+            result.append_alternate("S");
+        } else {
+            result.append("S");
+        }
+        if value.contains(index + 1, 1, &["S", "Z"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+fn handle_sc(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if value.char_at(index + 2) == 'H' {
+        // Schlesinger's rule
+        if value.contains(index + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+            // Dutch origin, e.g. "school", "schooner"
+            if value.contains(index + 3, 2, &["ER", "EN"]) {
+                // "schermerhorn", "schenker"
+                result.append_both("X", "SK");
+            } else {
+                result.append("SK");
+            }
+        } else if index == 0 && !is_vowel(value.char_at(3)) && value.char_at(3) != 'W' {
+            result.append_both("X", "S");
+        } else {
+            result.append("X");
+        }
+    } else if value.contains(index + 2, 1, &["I", "E", "Y"]) {
+        result.append("S");
+    } else {
+        result.append("SK");
+    }
+    index + 3
+}
+
+fn handle_t(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if value.contains(index, 4, &["TION"]) || value.contains(index, 3, &["TIA", "TCH"]) {
+        result.append("X");
+        index + 3
+    } else if value.contains(index, 2, &["TH"]) || value.contains(index, 3, &["TTH"]) {
+        if value.contains(index + 2, 2, &["OM", "AM"])
+            || value.contains(0, 4, &["VAN ", "VON "])
+            || value.contains(0, 3, &["SCH"])
+        {
+            // "thomas", "thames" or Germanic
+            result.append("T");
+        } else {
+            result.append_both("0", "T");
+        }
+        index + 2
+    } else {
+        result.append("T");
+        if value.contains(index + 1, 1, &["T", "D"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+fn handle_w(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if value.contains(index, 2, &["WR"]) {
+        // can also be in middle of word
+        result.append("R");
+        index + 2
+    } else if index == 0
+        && (is_vowel(value.char_at(index + 1)) || value.contains(index, 2, &["WH"]))
+    {
+        if is_vowel(value.char_at(index + 1)) {
+            // "Wasserman" should match "Vasserman"
+            result.append_both("A", "F");
+        } else {
+            // "Uomo" should match "Womo"
+            result.append("A");
+        }
+        index + 1
+    } else if (index == value.len() - 1 && is_vowel(value.char_at(index - 1)))
+        || value.contains(index - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+        || value.contains(0, 3, &["SCH"])
+    {
+        // "Arnow" should match "Arnoff"
+        result.append_alternate("F");
+        index + 1
+    } else if value.contains(index, 4, &["WICZ", "WITZ"]) {
+        // Polish, e.g. "filipowicz"
+        result.append_both("TS", "FX");
+        index + 4
+    } else {
+        index + 1
+    }
+}
+
+fn handle_x(value: &Word, result: &mut DoubleMetaphoneResult, index: i64) -> i64 {
+    if index == 0 {
+        result.append("S");
+        return index + 1;
+    }
+    if !(index == value.len() - 1
+        && (value.contains(index - 3, 3, &["IAU", "EAU"])
+            || value.contains(index - 2, 2, &["AU", "OU"])))
+    {
+        // not French, e.g. "breaux"
+        result.append("KS");
+    }
+    if value.contains(index + 1, 1, &["C", "X"]) {
+        index + 2
+    } else {
+        index + 1
+    }
+}
+
+fn handle_z(
+    value: &Word,
+    result: &mut DoubleMetaphoneResult,
+    index: i64,
+    slavo_germanic: bool,
+) -> i64 {
+    if value.char_at(index + 1) == 'H' {
+        // Chinese pinyin, e.g. "zhao"
+        result.append("J");
+        return index + 2;
+    }
+    if value.contains(index + 1, 2, &["ZO", "ZI", "ZA"])
+        || (slavo_germanic && index > 0 && value.char_at(index - 1) != 'T')
+    {
+        result.append_both("S", "TS");
+    } else {
+        result.append("S");
+    }
+    skip_double(value, index, 'Z')
+}
+
+fn condition_c0(value: &Word, index: i64) -> bool {
+    if value.contains(index, 4, &["CHIA"]) {
+        true
+    } else if index <= 1
+        || is_vowel(value.char_at(index - 2))
+        || !value.contains(index - 1, 3, &["ACH"])
+    {
+        false
+    } else {
+        let c = value.char_at(index + 2);
+        (c != 'I' && c != 'E') || value.contains(index - 2, 6, &["BACHER", "MACHER"])
+    }
+}
+
+fn condition_ch0(value: &Word, index: i64) -> bool {
+    index == 0
+        && (value.contains(index + 1, 5, &["HARAC", "HARIS"])
+            || value.contains(index + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+        && !value.contains(0, 5, &["CHORE"])
+}
+
+fn condition_ch1(value: &Word, index: i64) -> bool {
+    value.contains(0, 4, &["VAN ", "VON "])
+        || value.contains(0, 3, &["SCH"])
+        || value.contains(index - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+        || value.contains(index + 2, 1, &["T", "S"])
+        || ((value.contains(index - 1, 1, &["A", "O", "U", "E"]) || index == 0)
+            && (value.contains(index + 2, 1, &L_R_N_M_B_H_F_V_W_SPACE)
+                || index + 1 == value.len() - 1))
+}
+
+fn condition_l0(value: &Word, index: i64) -> bool {
+    if index == value.len() - 3 && value.contains(index - 1, 4, &["ILLO", "ILLA", "ALLE"]) {
+        return true;
+    }
+    (value.contains(value.len() - 2, 2, &["AS", "OS"])
+        || value.contains(value.len() - 1, 1, &["A", "O"]))
+        && value.contains(index - 1, 4, &["ALLE"])
+}
+
+fn condition_m0(value: &Word, index: i64) -> bool {
+    if value.char_at(index + 1) == 'M' {
+        return true;
+    }
+    value.contains(index - 1, 3, &["UMB"])
+        && (index + 1 == value.len() - 1 || value.contains(index + 2, 2, &["ER"]))
+}
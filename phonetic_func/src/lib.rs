@@ -0,0 +1,508 @@
+use gandiva_rust_udf_macro::udf;
+
+mod double_metaphone;
+
+// Codes for the letters A-Z, used by American Soundex.
+const SOUNDEX_MAPPING: &[u8; 26] = b"01230120022455012623010202";
+// Codes for the letters A-Z, used by Refined Soundex.
+const REFINED_SOUNDEX_MAPPING: &[u8; 26] = b"01360240043788015936020505";
+const DEFAULT_METAPHONE_LENGTH: i64 = 4;
+const NYSIIS_LENGTH: usize = 6;
+const CAVERPHONE_LENGTH: usize = 10;
+
+fn ascii_letters(text: &str) -> Vec<u8> {
+    text.bytes()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn is_vowel(c: u8) -> bool {
+    matches!(c, b'A' | b'E' | b'I' | b'O' | b'U')
+}
+
+// American Soundex, e.g. "Robert" and "Rupert" ==> R163
+#[udf]
+pub fn soundex(text: &str) -> String {
+    let letters = ascii_letters(text);
+    let Some(&first) = letters.first() else {
+        return String::new();
+    };
+    let mut code = vec![first];
+    let mut last = SOUNDEX_MAPPING[(first - b'A') as usize];
+    for &c in &letters[1..] {
+        // H and W do not separate letters with the same code
+        if c == b'H' || c == b'W' {
+            continue;
+        }
+        let digit = SOUNDEX_MAPPING[(c - b'A') as usize];
+        if digit != b'0' && digit != last {
+            code.push(digit);
+            if code.len() == 4 {
+                break;
+            }
+        }
+        last = digit;
+    }
+    code.resize(4, b'0');
+    String::from_utf8(code).unwrap()
+}
+
+// Refined Soundex keeps every letter and is not truncated, e.g. "testing" ==> T6036084
+#[udf]
+pub fn refined_soundex(text: &str) -> String {
+    let letters = ascii_letters(text);
+    let Some(&first) = letters.first() else {
+        return String::new();
+    };
+    let mut code = vec![first];
+    let mut last = None;
+    for &c in &letters {
+        let digit = REFINED_SOUNDEX_MAPPING[(c - b'A') as usize];
+        if last != Some(digit) {
+            code.push(digit);
+            last = Some(digit);
+        }
+    }
+    String::from_utf8(code).unwrap()
+}
+
+#[udf(name = "metaphone")]
+pub fn metaphone_default(text: &str) -> String {
+    metaphone(text, DEFAULT_METAPHONE_LENGTH)
+}
+
+#[udf]
+pub fn metaphone(text: &str, max_length: i64) -> String {
+    let max_length = max_length.max(0) as usize;
+    let mut word = ascii_letters(text);
+    if word.len() <= 1 {
+        return String::from_utf8(word).unwrap();
+    }
+
+    // initial exceptions
+    match (word[0], word[1]) {
+        (b'K' | b'G' | b'P', b'N') | (b'A', b'E') | (b'W', b'R') => {
+            word.remove(0);
+        }
+        (b'W', b'H') => {
+            word.remove(0);
+            word[0] = b'W';
+        }
+        (b'X', _) => word[0] = b'S',
+        _ => {}
+    }
+
+    let at = |i: usize| word.get(i).copied().unwrap_or(0);
+    let is_front_vowel = |i: usize| matches!(at(i), b'E' | b'I' | b'Y');
+    let region = |i: usize, s: &[u8]| word[i..].starts_with(s);
+    let previous_is = |i: usize, c: u8| i > 0 && word[i - 1] == c;
+    let is_last = |i: usize| i + 1 == word.len();
+
+    let mut code = Vec::new();
+    let mut n = 0;
+    while code.len() < max_length && n < word.len() {
+        let symb = word[n];
+        // skip duplicate letters except C
+        if symb != b'C' && previous_is(n, symb) {
+            n += 1;
+            continue;
+        }
+        match symb {
+            // vowels are only kept as the leading letter
+            b'A' | b'E' | b'I' | b'O' | b'U' if n == 0 => code.push(symb),
+            // silent in a trailing "MB"
+            b'B' if !(previous_is(n, b'M') && is_last(n)) => code.push(b'B'),
+            b'C' => {
+                if previous_is(n, b'S') && !is_last(n) && is_front_vowel(n + 1) {
+                    // silent in SCI, SCE, SCY
+                } else if region(n, b"CIA") {
+                    code.push(b'X');
+                } else if !is_last(n) && is_front_vowel(n + 1) {
+                    code.push(b'S');
+                } else if previous_is(n, b'S') && at(n + 1) == b'H' {
+                    code.push(b'K');
+                } else if at(n + 1) == b'H' {
+                    if n == 0 && word.len() >= 3 && is_vowel(at(2)) {
+                        code.push(b'K');
+                    } else {
+                        code.push(b'X');
+                    }
+                } else {
+                    code.push(b'K');
+                }
+            }
+            b'D' => {
+                if !is_last(n + 1) && at(n + 1) == b'G' && is_front_vowel(n + 2) {
+                    // DGE, DGI, DGY ==> J
+                    code.push(b'J');
+                    n += 2;
+                } else {
+                    code.push(b'T');
+                }
+            }
+            b'G' => {
+                let silent = (is_last(n + 1) && at(n + 1) == b'H')
+                    || (!is_last(n + 1) && at(n + 1) == b'H' && !is_vowel(at(n + 2)))
+                    || (n > 0 && (region(n, b"GN") || region(n, b"GNED")));
+                if !silent {
+                    let hard = previous_is(n, b'G');
+                    if !is_last(n) && is_front_vowel(n + 1) && !hard {
+                        code.push(b'J');
+                    } else {
+                        code.push(b'K');
+                    }
+                }
+            }
+            b'H' => {
+                let after_varson = n > 0 && matches!(word[n - 1], b'C' | b'S' | b'P' | b'T' | b'G');
+                if !is_last(n) && !after_varson && is_vowel(at(n + 1)) {
+                    code.push(b'H');
+                }
+            }
+            b'F' | b'J' | b'L' | b'M' | b'N' | b'R' => code.push(symb),
+            b'K' if !previous_is(n, b'C') => code.push(b'K'),
+            b'P' => code.push(if at(n + 1) == b'H' { b'F' } else { b'P' }),
+            b'Q' => code.push(b'K'),
+            b'S' => {
+                if region(n, b"SH") || region(n, b"SIO") || region(n, b"SIA") {
+                    code.push(b'X');
+                } else {
+                    code.push(b'S');
+                }
+            }
+            b'T' => {
+                if region(n, b"TIA") || region(n, b"TIO") {
+                    code.push(b'X');
+                } else if region(n, b"TCH") {
+                    // silent in TCH
+                } else if region(n, b"TH") {
+                    // TH ==> 0 (theta)
+                    code.push(b'0');
+                } else {
+                    code.push(b'T');
+                }
+            }
+            b'V' => code.push(b'F'),
+            // silent if not followed by a vowel
+            b'W' | b'Y' if !is_last(n) && is_vowel(at(n + 1)) => code.push(symb),
+            b'X' => code.extend_from_slice(b"KS"),
+            b'Z' => code.push(b'S'),
+            _ => {}
+        }
+        n += 1;
+    }
+    code.truncate(max_length);
+    String::from_utf8(code).unwrap()
+}
+
+#[udf(name = "double_metaphone_primary")]
+pub fn double_metaphone_primary_default(text: &str) -> String {
+    double_metaphone_primary(text, DEFAULT_METAPHONE_LENGTH)
+}
+
+#[udf]
+pub fn double_metaphone_primary(text: &str, max_length: i64) -> String {
+    double_metaphone::double_metaphone(text, max_length.max(0) as usize, false)
+}
+
+#[udf(name = "double_metaphone_alternate")]
+pub fn double_metaphone_alternate_default(text: &str) -> String {
+    double_metaphone_alternate(text, DEFAULT_METAPHONE_LENGTH)
+}
+
+#[udf]
+pub fn double_metaphone_alternate(text: &str, max_length: i64) -> String {
+    double_metaphone::double_metaphone(text, max_length.max(0) as usize, true)
+}
+
+// New York State Identification and Intelligence System, truncated to 6 characters
+#[udf]
+pub fn nysiis(text: &str) -> String {
+    let mut word = ascii_letters(text);
+    if word.is_empty() {
+        return String::new();
+    }
+
+    // translate first characters of name
+    let prefixes: [(&[u8], &[u8]); 5] = [
+        (b"MAC", b"MCC"),
+        (b"KN", b"NN"),
+        (b"K", b"C"),
+        (b"PH", b"FF"),
+        (b"PF", b"FF"),
+    ];
+    if let Some((from, to)) = prefixes.iter().find(|(from, _)| word.starts_with(from)) {
+        word.splice(..from.len(), to.iter().copied());
+    }
+    if word.starts_with(b"SCH") {
+        word[..3].copy_from_slice(b"SSS");
+    }
+    // translate last characters of name
+    if word.ends_with(b"EE") || word.ends_with(b"IE") {
+        word.truncate(word.len() - 2);
+        word.push(b'Y');
+    } else if [&b"DT"[..], b"RT", b"RD", b"NT", b"ND"]
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        word.truncate(word.len() - 2);
+        word.push(b'D');
+    }
+
+    let mut key = vec![word[0]];
+    for i in 1..word.len() {
+        let prev = word[i - 1];
+        let curr = word[i];
+        let next = word.get(i + 1).copied().unwrap_or(b' ');
+        let after_next = word.get(i + 2).copied().unwrap_or(b' ');
+        let transcoded: &[u8] = match curr {
+            b'E' if next == b'V' => b"AF",
+            _ if is_vowel(curr) => b"A",
+            b'Q' => b"G",
+            b'Z' => b"S",
+            b'M' => b"N",
+            b'K' if next == b'N' => b"NN",
+            b'K' => b"C",
+            b'S' if next == b'C' && after_next == b'H' => b"SSS",
+            b'P' if next == b'H' => b"FF",
+            b'H' if !is_vowel(prev) || !is_vowel(next) => &word[i - 1..i],
+            b'W' if is_vowel(prev) => &word[i - 1..i],
+            _ => &word[i..i + 1],
+        };
+        let transcoded = transcoded.to_vec();
+        word[i..i + transcoded.len()].copy_from_slice(&transcoded);
+        // only append the current char if it differs from the last one
+        if word[i] != word[i - 1] {
+            key.push(word[i]);
+        }
+    }
+
+    if key.len() > 1 {
+        if key.last() == Some(&b'S') {
+            key.pop();
+        }
+        if key.ends_with(b"AY") && key.len() > 2 {
+            key.remove(key.len() - 2);
+        } else if key.last() == Some(&b'A') {
+            key.pop();
+        }
+    }
+    key.truncate(NYSIIS_LENGTH);
+    String::from_utf8(key).unwrap()
+}
+
+fn replace_start(text: String, from: &str, to: &str) -> String {
+    match text.strip_prefix(from) {
+        Some(rest) => format!("{}{}", to, rest),
+        None => text,
+    }
+}
+
+fn replace_end(text: String, from: &str, to: &str) -> String {
+    match text.strip_suffix(from) {
+        Some(rest) => format!("{}{}", rest, to),
+        None => text,
+    }
+}
+
+fn collapse(text: String, from: char, to: char) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        if c != from {
+            result.push(c);
+        } else if previous != Some(from) {
+            result.push(to);
+        }
+        previous = Some(c);
+    }
+    result
+}
+
+// Caverphone 2.0, always 10 characters long, e.g. "Stevenson" ==> STFNSN1111
+#[udf]
+pub fn caverphone(text: &str) -> String {
+    let mut txt: String = text
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    txt = replace_end(txt, "e", "");
+    for (from, to) in [
+        ("cough", "cou2f"),
+        ("rough", "rou2f"),
+        ("tough", "tou2f"),
+        ("enough", "enou2f"),
+        ("trough", "trou2f"),
+        ("gn", "2n"),
+    ] {
+        txt = replace_start(txt, from, to);
+    }
+    txt = replace_end(txt, "mb", "m2");
+    for (from, to) in [
+        ("cq", "2q"),
+        ("ci", "si"),
+        ("ce", "se"),
+        ("cy", "sy"),
+        ("tch", "2ch"),
+        ("c", "k"),
+        ("q", "k"),
+        ("x", "k"),
+        ("v", "f"),
+        ("dg", "2g"),
+        ("tio", "sio"),
+        ("tia", "sia"),
+        ("d", "t"),
+        ("ph", "fh"),
+        ("b", "p"),
+        ("sh", "s2"),
+        ("z", "s"),
+    ] {
+        txt = txt.replace(from, to);
+    }
+    if txt.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        txt.replace_range(..1, "A");
+    }
+    txt = txt.replace(['a', 'e', 'i', 'o', 'u'], "3");
+    txt = txt.replace('j', "y");
+    txt = replace_start(txt, "y3", "Y3");
+    txt = replace_start(txt, "y", "A");
+    txt = txt.replace('y', "3");
+    txt = txt.replace("3gh3", "3kh3");
+    txt = txt.replace("gh", "22");
+    txt = txt.replace('g', "k");
+    for (from, to) in [
+        ('s', 'S'),
+        ('t', 'T'),
+        ('p', 'P'),
+        ('k', 'K'),
+        ('f', 'F'),
+        ('m', 'M'),
+        ('n', 'N'),
+    ] {
+        txt = collapse(txt, from, to);
+    }
+    txt = txt.replace("w3", "W3");
+    txt = txt.replace("wh3", "Wh3");
+    txt = replace_end(txt, "w", "3");
+    txt = txt.replace('w', "2");
+    txt = replace_start(txt, "h", "A");
+    txt = txt.replace('h', "2");
+    txt = txt.replace("r3", "R3");
+    txt = replace_end(txt, "r", "3");
+    txt = txt.replace('r', "2");
+    txt = txt.replace("l3", "L3");
+    txt = replace_end(txt, "l", "3");
+    txt = txt.replace('l', "2");
+    txt = txt.replace('2', "");
+    txt = replace_end(txt, "3", "A");
+    txt = txt.replace('3', "");
+
+    let mut code: String = txt.chars().take(CAVERPHONE_LENGTH).collect();
+    while code.len() < CAVERPHONE_LENGTH {
+        code.push('1');
+    }
+    code
+}
+
+// Codex of the Match Rating Approach (Western Airlines, 1977), e.g. "Byrne" ==> BYRN
+#[udf]
+pub fn match_rating_codex(text: &str) -> String {
+    let letters = ascii_letters(text);
+    if letters.len() <= 1 {
+        return String::new();
+    }
+
+    // drop all vowels but a leading one
+    let mut codex = vec![letters[0]];
+    codex.extend(letters[1..].iter().filter(|c| !is_vowel(**c)));
+    // collapse double consonants
+    codex.dedup_by(|a, b| a == b && !is_vowel(*a));
+
+    if codex.len() > 6 {
+        codex.drain(3..codex.len() - 3);
+    }
+    String::from_utf8(codex).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soundex() {
+        assert_eq!(soundex("Robert"), "R163");
+        assert_eq!(soundex("Rupert"), "R163");
+        assert_eq!(soundex("Ashcraft"), "A261");
+        assert_eq!(soundex("Tymczak"), "T522");
+        assert_eq!(soundex("Pfister"), "P236");
+        assert_eq!(soundex("Lee"), "L000");
+        assert_eq!(soundex("O'Hara"), "O600");
+        assert_eq!(soundex(""), "");
+    }
+
+    #[test]
+    fn test_refined_soundex() {
+        assert_eq!(refined_soundex("testing"), "T6036084");
+        assert_eq!(refined_soundex("The"), "T60");
+        assert_eq!(refined_soundex("Braz"), "B1905");
+        assert_eq!(refined_soundex(""), "");
+    }
+
+    #[test]
+    fn test_metaphone() {
+        assert_eq!(metaphone_default("Thomas"), "0MS");
+        assert_eq!(metaphone_default("knight"), "NT");
+        assert_eq!(metaphone_default("Wright"), "RT");
+        assert_eq!(metaphone_default("Xavier"), "SFR");
+        assert_eq!(metaphone("Schmidt", 10), "SKMTT");
+        assert_eq!(metaphone("discrimination", 10), "TSKRMNXN");
+        assert_eq!(metaphone_default(""), "");
+    }
+
+    #[test]
+    fn test_double_metaphone() {
+        assert_eq!(double_metaphone_primary_default("Smith"), "SM0");
+        assert_eq!(double_metaphone_alternate_default("Smith"), "XMT");
+        assert_eq!(double_metaphone_primary_default("Schmidt"), "XMT");
+        assert_eq!(double_metaphone_alternate_default("Schmidt"), "SMT");
+        assert_eq!(double_metaphone_primary_default("Thomas"), "TMS");
+        assert_eq!(double_metaphone_primary_default("Jose"), "HS");
+        assert_eq!(double_metaphone_primary_default("Michael"), "MKL");
+        assert_eq!(double_metaphone_alternate_default("Michael"), "MXL");
+        assert_eq!(double_metaphone_primary("Filipowicz", 10), "FLPTS");
+        assert_eq!(double_metaphone_alternate("Filipowicz", 10), "FLPFX");
+        assert_eq!(double_metaphone_primary_default(""), "");
+    }
+
+    #[test]
+    fn test_nysiis() {
+        assert_eq!(nysiis("Macintosh"), "MCANT");
+        assert_eq!(nysiis("Knuth"), "NAT");
+        assert_eq!(nysiis("Schmidt"), "SNAD");
+        assert_eq!(nysiis("Bishop"), "BASAP");
+        assert_eq!(nysiis("Mitchell"), "MATCAL");
+        assert_eq!(nysiis(""), "");
+    }
+
+    #[test]
+    fn test_caverphone() {
+        assert_eq!(caverphone("Peter"), "PTA1111111");
+        assert_eq!(caverphone("ready"), "RTA1111111");
+        assert_eq!(caverphone("social"), "SSA1111111");
+        assert_eq!(caverphone("Karleen"), "KLN1111111");
+        assert_eq!(caverphone("Dyun"), "TN11111111");
+        assert_eq!(caverphone("Stevenson"), "STFNSN1111");
+        assert_eq!(caverphone(""), "1111111111");
+    }
+
+    #[test]
+    fn test_match_rating_codex() {
+        assert_eq!(match_rating_codex("Byrne"), "BYRN");
+        assert_eq!(match_rating_codex("Catherine"), "CTHRN");
+        assert_eq!(match_rating_codex("Abernathy"), "ABRTHY");
+        assert_eq!(match_rating_codex("Smith"), "SMTH");
+        assert_eq!(match_rating_codex("a"), "");
+    }
+}
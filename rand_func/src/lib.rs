@@ -0,0 +1,165 @@
+use gandiva_rust_udf_macro::udf;
+use siphasher::sip::SipHasher13;
+use std::f64::consts::PI;
+use std::hash::Hasher;
+
+// Deterministic random values: the same seed and row key always give the same
+// value, on any machine, partition or run. Integer row keys hash like their
+// decimal text, so row key 42 and '42' agree.
+struct Stream {
+    state: u64,
+}
+
+impl Stream {
+    fn new(seed: i64, row_key: &str) -> Stream {
+        let mut hasher = SipHasher13::new_with_keys(seed as u64, 0);
+        hasher.write(row_key.as_bytes());
+        Stream {
+            state: hasher.finish(),
+        }
+    }
+
+    // splitmix64
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1) with 53 bits of precision.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Uniform in [0, 1).
+#[udf]
+pub fn rand_uniform(seed: i64, row_key: &str) -> f64 {
+    Stream::new(seed, row_key).next_f64()
+}
+
+#[udf(name = "rand_uniform")]
+pub fn rand_uniform_int_key(seed: i64, row_key: i64) -> f64 {
+    rand_uniform(seed, &row_key.to_string())
+}
+
+// Normally distributed, using the Box-Muller transform.
+#[udf]
+pub fn rand_normal(seed: i64, row_key: &str, mean: f64, std: f64) -> Result<f64, String> {
+    if !(std >= 0.0 && std.is_finite()) {
+        return Err(format!(
+            "Standard deviation must be finite and not negative, got std: {}",
+            std
+        ));
+    }
+    let mut stream = Stream::new(seed, row_key);
+    // 1 - u lies in (0, 1], so the logarithm stays finite
+    let u1 = 1.0 - stream.next_f64();
+    let u2 = stream.next_f64();
+    let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+    Ok(mean + std * z)
+}
+
+#[udf(name = "rand_normal")]
+pub fn rand_normal_int_key(seed: i64, row_key: i64, mean: f64, std: f64) -> Result<f64, String> {
+    rand_normal(seed, &row_key.to_string(), mean, std)
+}
+
+// Keeps a row with probability rate. Rows kept at a lower rate are also kept at
+// any higher rate with the same seed, so samples nest.
+#[udf]
+pub fn sample_bernoulli(row_key: &str, rate: f64, seed: i64) -> Result<bool, String> {
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(format!("Rate must be between 0 and 1, got rate: {}", rate));
+    }
+    Ok(rand_uniform(seed, row_key) < rate)
+}
+
+#[udf(name = "sample_bernoulli")]
+pub fn sample_bernoulli_int_key(row_key: i64, rate: f64, seed: i64) -> Result<bool, String> {
+    sample_bernoulli(&row_key.to_string(), rate, seed)
+}
+
+// A version 4 UUID whose random bits come from the seed and row key.
+#[udf]
+pub fn uuid_v4_seeded(seed: i64, row_key: &str) -> String {
+    let mut stream = Stream::new(seed, row_key);
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&stream.next_u64().to_be_bytes());
+    bytes[8..].copy_from_slice(&stream.next_u64().to_be_bytes());
+    uuid::Builder::from_random_bytes(bytes)
+        .into_uuid()
+        .to_string()
+}
+
+#[udf(name = "uuid_v4_seeded")]
+pub fn uuid_v4_seeded_int_key(seed: i64, row_key: i64) -> String {
+    uuid_v4_seeded(seed, &row_key.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rand_uniform() {
+        let value = rand_uniform(1, "row-1");
+        assert!((0.0..1.0).contains(&value));
+        assert_eq!(rand_uniform(1, "row-1"), value);
+        assert_ne!(rand_uniform(2, "row-1"), value);
+        assert_ne!(rand_uniform(1, "row-2"), value);
+        assert_eq!(rand_uniform_int_key(7, 42), rand_uniform(7, "42"));
+
+        let mean = (0..10000).map(|i| rand_uniform_int_key(3, i)).sum::<f64>() / 10000.0;
+        assert!((mean - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_rand_normal() {
+        assert_eq!(rand_normal(1, "a", 0.0, 1.0), rand_normal(1, "a", 0.0, 1.0));
+        assert_eq!(rand_normal(1, "a", 5.0, 0.0), Ok(5.0));
+
+        let values: Vec<f64> = (0..10000)
+            .map(|i| rand_normal_int_key(3, i, 10.0, 2.0).unwrap())
+            .collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        assert!((mean - 10.0).abs() < 0.1);
+        assert!((variance.sqrt() - 2.0).abs() < 0.1);
+        assert_eq!(
+            rand_normal(1, "a", 0.0, -1.0),
+            Err("Standard deviation must be finite and not negative, got std: -1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sample_bernoulli() {
+        let kept = (0..10000)
+            .filter(|i| sample_bernoulli_int_key(*i, 0.1, 5).unwrap())
+            .count();
+        assert!((900..1100).contains(&kept));
+        assert_eq!(sample_bernoulli("a", 0.0, 1), Ok(false));
+        assert_eq!(sample_bernoulli("a", 1.0, 1), Ok(true));
+        // a 10% sample is contained in the 20% sample with the same seed
+        assert!(
+            (0..1000).all(|i| !sample_bernoulli_int_key(i, 0.1, 5).unwrap()
+                || sample_bernoulli_int_key(i, 0.2, 5).unwrap())
+        );
+        assert_eq!(
+            sample_bernoulli("a", 1.5, 1),
+            Err("Rate must be between 0 and 1, got rate: 1.5".to_string())
+        );
+    }
+
+    #[test]
+    fn test_uuid_v4_seeded() {
+        let value = uuid_v4_seeded(1, "row-1");
+        assert_eq!(value.len(), 36);
+        assert_eq!(&value[14..15], "4");
+        assert_eq!(uuid_v4_seeded(1, "row-1"), value);
+        assert_ne!(uuid_v4_seeded(2, "row-1"), value);
+        assert_eq!(uuid_v4_seeded_int_key(1, 42), uuid_v4_seeded(1, "42"));
+    }
+}
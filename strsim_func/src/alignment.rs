@@ -0,0 +1,93 @@
+// Subsequence, substring and sequence alignment measures.
+
+// Length of the longest common subsequence.
+pub fn lcs_len<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut row = vec![0usize; b.len() + 1];
+    for ca in a {
+        let mut diagonal = 0;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+// Start in a and length of the longest common substring, the first one in a on ties.
+pub fn longest_common_substring<T: PartialEq>(a: &[T], b: &[T]) -> (usize, usize) {
+    let mut row = vec![0usize; b.len() + 1];
+    let (mut best_end, mut best_len) = (0, 0);
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = 0;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb { diagonal + 1 } else { 0 };
+            if row[j + 1] > best_len {
+                best_len = row[j + 1];
+                best_end = i + 1;
+            }
+            diagonal = above;
+        }
+    }
+    (best_end - best_len, best_len)
+}
+
+pub fn common_prefix_len<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scoring {
+    pub matched: f64,
+    pub mismatch: f64,
+    pub gap: f64,
+}
+
+// Best local alignment score with a linear gap penalty.
+pub fn smith_waterman<T: PartialEq>(a: &[T], b: &[T], scoring: Scoring) -> f64 {
+    let mut prev = vec![0.0; b.len() + 1];
+    let mut curr = vec![0.0; b.len() + 1];
+    let mut best: f64 = 0.0;
+    for ca in a {
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = if ca == cb {
+                scoring.matched
+            } else {
+                scoring.mismatch
+            };
+            curr[j + 1] = (prev[j] + substitution)
+                .max(prev[j + 1] + scoring.gap)
+                .max(curr[j] + scoring.gap)
+                .max(0.0);
+            best = best.max(curr[j + 1]);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    best
+}
+
+// Global alignment score with a linear gap penalty.
+pub fn needleman_wunsch<T: PartialEq>(a: &[T], b: &[T], scoring: Scoring) -> f64 {
+    let mut prev: Vec<f64> = (0..=b.len()).map(|j| j as f64 * scoring.gap).collect();
+    let mut curr = vec![0.0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = (i + 1) as f64 * scoring.gap;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = if ca == cb {
+                scoring.matched
+            } else {
+                scoring.mismatch
+            };
+            curr[j + 1] = (prev[j] + substitution)
+                .max(prev[j + 1] + scoring.gap)
+                .max(curr[j] + scoring.gap);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
@@ -0,0 +1,123 @@
+// RapidFuzz style ratios, all scored from 0 to 100.
+use crate::alignment::lcs_len;
+use std::collections::BTreeSet;
+
+// Normalized InDel similarity, the same measure as python-Levenshtein's ratio.
+fn chars_ratio(a: &[char], b: &[char]) -> f64 {
+    let total = a.len() + b.len();
+    if total == 0 {
+        return 100.0;
+    }
+    200.0 * lcs_len(a, b) as f64 / total as f64
+}
+
+pub fn ratio(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    chars_ratio(&a, &b)
+}
+
+// Best ratio of the shorter string against any equally long window of the longer one.
+pub fn partial_ratio(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if shorter.is_empty() {
+        return if longer.is_empty() { 100.0 } else { 0.0 };
+    }
+
+    let width = shorter.len();
+    let mut best: f64 = 0.0;
+    // windows hanging over the start and the end of the longer string
+    for len in 1..width {
+        best = best.max(chars_ratio(&shorter, &longer[..len]));
+        best = best.max(chars_ratio(&shorter, &longer[longer.len() - len..]));
+    }
+    for window in longer.windows(width) {
+        best = best.max(chars_ratio(&shorter, window));
+        if best == 100.0 {
+            break;
+        }
+    }
+    best
+}
+
+fn tokens(text: &str) -> BTreeSet<&str> {
+    text.split_whitespace().collect()
+}
+
+fn sorted_tokens(text: &str) -> String {
+    let mut tokens: Vec<&str> = text.split_whitespace().collect();
+    tokens.sort_unstable();
+    tokens.join(" ")
+}
+
+pub fn token_sort_ratio(a: &str, b: &str) -> f64 {
+    ratio(&sorted_tokens(a), &sorted_tokens(b))
+}
+
+fn partial_token_sort_ratio(a: &str, b: &str) -> f64 {
+    partial_ratio(&sorted_tokens(a), &sorted_tokens(b))
+}
+
+// Compares the shared tokens against each string's shared plus remaining tokens.
+fn token_set_scores(a: &str, b: &str, scorer: fn(&str, &str) -> f64) -> f64 {
+    let tokens_a = tokens(a);
+    let tokens_b = tokens(b);
+    if tokens_a.is_empty() || tokens_b.is_empty() {
+        return 0.0;
+    }
+
+    let join = |tokens: Vec<&&str>| tokens.into_iter().copied().collect::<Vec<_>>().join(" ");
+    let intersection = join(tokens_a.intersection(&tokens_b).collect());
+    let diff_ab = join(tokens_a.difference(&tokens_b).collect());
+    let diff_ba = join(tokens_b.difference(&tokens_a).collect());
+    if !intersection.is_empty() && (diff_ab.is_empty() || diff_ba.is_empty()) {
+        return 100.0;
+    }
+
+    let combine = |diff: &str| {
+        if intersection.is_empty() {
+            diff.to_string()
+        } else {
+            format!("{} {}", intersection, diff)
+        }
+    };
+    let combined_ab = combine(&diff_ab);
+    let combined_ba = combine(&diff_ba);
+    scorer(&intersection, &combined_ab)
+        .max(scorer(&intersection, &combined_ba))
+        .max(scorer(&combined_ab, &combined_ba))
+}
+
+pub fn token_set_ratio(a: &str, b: &str) -> f64 {
+    token_set_scores(a, b, ratio)
+}
+
+fn partial_token_set_ratio(a: &str, b: &str) -> f64 {
+    token_set_scores(a, b, partial_ratio)
+}
+
+// fuzzywuzzy's WRatio: picks the most suitable ratio depending on the length difference.
+pub fn weighted_ratio(a: &str, b: &str) -> f64 {
+    const UNBASE_SCALE: f64 = 0.95;
+
+    let len_a = a.chars().count();
+    let len_b = b.chars().count();
+    if len_a == 0 || len_b == 0 {
+        return 0.0;
+    }
+
+    let base = ratio(a, b);
+    let len_ratio = len_a.max(len_b) as f64 / len_a.min(len_b) as f64;
+    if len_ratio < 1.5 {
+        return base
+            .max(token_sort_ratio(a, b) * UNBASE_SCALE)
+            .max(token_set_ratio(a, b) * UNBASE_SCALE);
+    }
+
+    let partial_scale = if len_ratio < 8.0 { 0.9 } else { 0.6 };
+    base.max(partial_ratio(a, b) * partial_scale)
+        .max(partial_token_sort_ratio(a, b) * UNBASE_SCALE * partial_scale)
+        .max(partial_token_set_ratio(a, b) * UNBASE_SCALE * partial_scale)
+}
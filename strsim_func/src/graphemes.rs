@@ -0,0 +1,65 @@
+// Edit distances over extended grapheme clusters, so that an emoji sequence or a
+// letter with combining marks counts as a single edit.
+use std::mem;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn graphemes(text: &str) -> Vec<&str> {
+    text.graphemes(true).collect()
+}
+
+fn normalize(distance: usize, a: &[&str], b: &[&str]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    1.0 - distance as f64 / a.len().max(b.len()) as f64
+}
+
+pub fn normalized_levenshtein(a: &str, b: &str) -> f64 {
+    let (a, b) = (graphemes(a), graphemes(b));
+    normalize(strsim::generic_levenshtein(&a, &b), &a, &b)
+}
+
+pub fn damerau_levenshtein(a: &str, b: &str) -> usize {
+    strsim::generic_damerau_levenshtein(&graphemes(a), &graphemes(b))
+}
+
+pub fn normalized_damerau_levenshtein(a: &str, b: &str) -> f64 {
+    let (a, b) = (graphemes(a), graphemes(b));
+    normalize(strsim::generic_damerau_levenshtein(&a, &b), &a, &b)
+}
+
+// Optimal string alignment, following strsim::osa_distance.
+pub fn osa_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (graphemes(a), graphemes(b));
+    let mut prev_two_distances: Vec<usize> = (0..b.len() + 1).collect();
+    let mut prev_distances: Vec<usize> = (0..b.len() + 1).collect();
+    let mut curr_distances: Vec<usize> = vec![0; b.len() + 1];
+
+    for (i, a_elem) in a.iter().enumerate() {
+        curr_distances[0] = i + 1;
+        for (j, b_elem) in b.iter().enumerate() {
+            let cost = usize::from(a_elem != b_elem);
+            curr_distances[j + 1] = (curr_distances[j] + 1)
+                .min(prev_distances[j + 1] + 1)
+                .min(prev_distances[j] + cost);
+            if i > 0 && j > 0 && a_elem != b_elem && *a_elem == b[j - 1] && *b_elem == a[i - 1] {
+                curr_distances[j + 1] = curr_distances[j + 1].min(prev_two_distances[j - 1] + 1);
+            }
+        }
+        mem::swap(&mut prev_two_distances, &mut prev_distances);
+        mem::swap(&mut prev_distances, &mut curr_distances);
+    }
+    prev_distances[b.len()]
+}
+
+pub fn hamming(a: &str, b: &str) -> strsim::HammingResult {
+    strsim::generic_hamming(a.graphemes(true), b.graphemes(true))
+}
+
+pub fn jaro(a: &str, b: &str) -> f64 {
+    strsim::generic_jaro(&graphemes(a), &graphemes(b))
+}
+
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    strsim::generic_jaro_winkler(&graphemes(a), &graphemes(b))
+}
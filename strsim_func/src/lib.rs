@@ -1,28 +1,34 @@
 use gandiva_rust_udf_macro::udf;
-use strsim::damerau_levenshtein;
-use strsim::hamming;
-use strsim::jaro;
-use strsim::jaro_winkler;
-use strsim::normalized_damerau_levenshtein;
-use strsim::normalized_levenshtein;
+use graphemes::damerau_levenshtein;
+use graphemes::hamming;
+use graphemes::jaro;
+use graphemes::jaro_winkler;
+use graphemes::normalized_damerau_levenshtein;
+use graphemes::normalized_levenshtein;
+
+mod alignment;
+mod fuzz;
+mod graphemes;
+mod ngram;
+mod preprocess;
+
+use alignment::Scoring;
+use graphemes::graphemes;
+use preprocess::Preprocess;
 
 #[udf]
 pub fn jaro_similarity(a: &str, b: &str) -> f64 {
-    return jaro(a, b);
+    jaro(a, b)
 }
 
 #[udf]
 pub fn jaro_winkler_similarity(a: &str, b: &str) -> f64 {
-    return jaro_winkler(a, b);
+    jaro_winkler(a, b)
 }
 
 #[udf]
 pub fn damerau_levenshtein_distance(a: &str, b: &str) -> i64 {
-    if let Ok(i64_value) = damerau_levenshtein(a, b).try_into() {
-        i64_value
-    } else {
-        0
-    }
+    damerau_levenshtein(a, b).try_into().unwrap_or(0)
 }
 
 #[udf]
@@ -36,26 +42,271 @@ pub fn hamming_distance(a: &str, b: &str) -> String {
 
 #[udf]
 pub fn normalized_damerau_levenshtein_distance(a: &str, b: &str) -> f64 {
-    return normalized_damerau_levenshtein(a, b);
+    normalized_damerau_levenshtein(a, b)
 }
 
 #[udf]
 pub fn normalized_levenshtein_distance(a: &str, b: &str) -> f64 {
-    return normalized_levenshtein(a, b);
+    normalized_levenshtein(a, b)
 }
 
 #[udf]
 pub fn osa_distance(a: &str, b: &str) -> i64 {
-    if let Ok(i64_value) = strsim::osa_distance(a, b).try_into() {
-        i64_value
-    } else {
-        0
-    }
+    graphemes::osa_distance(a, b).try_into().unwrap_or(0)
 }
 
 #[udf]
 pub fn sorensen_dice_similarity(a: &str, b: &str) -> f64 {
-    return strsim::sorensen_dice(a, b);
+    strsim::sorensen_dice(a, b)
+}
+
+// The _ci variants apply NFKD normalization, fold case and strip accents before
+// measuring, so "Café" and "cafe" compare as equal.
+#[udf]
+pub fn jaro_similarity_ci(a: &str, b: &str) -> f64 {
+    let preprocess = Preprocess::INSENSITIVE;
+    jaro_similarity(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf]
+pub fn jaro_winkler_similarity_ci(a: &str, b: &str) -> f64 {
+    let preprocess = Preprocess::INSENSITIVE;
+    jaro_winkler_similarity(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf]
+pub fn damerau_levenshtein_distance_ci(a: &str, b: &str) -> i64 {
+    let preprocess = Preprocess::INSENSITIVE;
+    damerau_levenshtein_distance(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf]
+pub fn hamming_distance_ci(a: &str, b: &str) -> String {
+    let preprocess = Preprocess::INSENSITIVE;
+    hamming_distance(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf]
+pub fn normalized_damerau_levenshtein_distance_ci(a: &str, b: &str) -> f64 {
+    let preprocess = Preprocess::INSENSITIVE;
+    normalized_damerau_levenshtein_distance(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf]
+pub fn normalized_levenshtein_distance_ci(a: &str, b: &str) -> f64 {
+    let preprocess = Preprocess::INSENSITIVE;
+    normalized_levenshtein_distance(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf]
+pub fn osa_distance_ci(a: &str, b: &str) -> i64 {
+    let preprocess = Preprocess::INSENSITIVE;
+    osa_distance(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf]
+pub fn sorensen_dice_similarity_ci(a: &str, b: &str) -> f64 {
+    let preprocess = Preprocess::INSENSITIVE;
+    sorensen_dice_similarity(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+// Fuzzy ratios score from 0 to 100. The two argument forms follow fuzzywuzzy's
+// defaults: ratio and partial_ratio compare the raw strings, while the token
+// based ratios lowercase and strip punctuation first. The three argument forms
+// take the preprocessing options explicitly, e.g. "lowercase,strip_punctuation"
+// or "case_fold,nfkd,strip_accents".
+#[udf]
+pub fn ratio(a: &str, b: &str) -> f64 {
+    fuzz::ratio(a, b)
+}
+
+#[udf(name = "ratio")]
+pub fn ratio_with_options(a: &str, b: &str, options: &str) -> Result<f64, String> {
+    let preprocess = Preprocess::parse(options)?;
+    Ok(fuzz::ratio(&preprocess.apply(a), &preprocess.apply(b)))
+}
+
+#[udf]
+pub fn partial_ratio(a: &str, b: &str) -> f64 {
+    fuzz::partial_ratio(a, b)
+}
+
+#[udf(name = "partial_ratio")]
+pub fn partial_ratio_with_options(a: &str, b: &str, options: &str) -> Result<f64, String> {
+    let preprocess = Preprocess::parse(options)?;
+    Ok(fuzz::partial_ratio(
+        &preprocess.apply(a),
+        &preprocess.apply(b),
+    ))
+}
+
+#[udf]
+pub fn token_sort_ratio(a: &str, b: &str) -> f64 {
+    let preprocess = Preprocess::FULL;
+    fuzz::token_sort_ratio(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf(name = "token_sort_ratio")]
+pub fn token_sort_ratio_with_options(a: &str, b: &str, options: &str) -> Result<f64, String> {
+    let preprocess = Preprocess::parse(options)?;
+    Ok(fuzz::token_sort_ratio(
+        &preprocess.apply(a),
+        &preprocess.apply(b),
+    ))
+}
+
+#[udf]
+pub fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let preprocess = Preprocess::FULL;
+    fuzz::token_set_ratio(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf(name = "token_set_ratio")]
+pub fn token_set_ratio_with_options(a: &str, b: &str, options: &str) -> Result<f64, String> {
+    let preprocess = Preprocess::parse(options)?;
+    Ok(fuzz::token_set_ratio(
+        &preprocess.apply(a),
+        &preprocess.apply(b),
+    ))
+}
+
+#[udf]
+pub fn weighted_ratio(a: &str, b: &str) -> f64 {
+    let preprocess = Preprocess::FULL;
+    fuzz::weighted_ratio(&preprocess.apply(a), &preprocess.apply(b))
+}
+
+#[udf(name = "weighted_ratio")]
+pub fn weighted_ratio_with_options(a: &str, b: &str, options: &str) -> Result<f64, String> {
+    let preprocess = Preprocess::parse(options)?;
+    Ok(fuzz::weighted_ratio(
+        &preprocess.apply(a),
+        &preprocess.apply(b),
+    ))
+}
+
+// N-gram measures take the n-gram size and optionally a mode, either "char"
+// (the default) or "word". Input shorter than n counts as a single n-gram.
+#[udf]
+pub fn jaccard_similarity(a: &str, b: &str, n: i64) -> Result<f64, String> {
+    jaccard_similarity_with_mode(a, b, n, "char")
+}
+
+#[udf(name = "jaccard_similarity")]
+pub fn jaccard_similarity_with_mode(a: &str, b: &str, n: i64, mode: &str) -> Result<f64, String> {
+    let mode = ngram::Mode::parse(mode)?;
+    let profile_a = ngram::profile(a, n, mode)?;
+    let profile_b = ngram::profile(b, n, mode)?;
+    Ok(ngram::jaccard(&profile_a, &profile_b))
+}
+
+#[udf]
+pub fn cosine_similarity(a: &str, b: &str, n: i64) -> Result<f64, String> {
+    cosine_similarity_with_mode(a, b, n, "char")
+}
+
+#[udf(name = "cosine_similarity")]
+pub fn cosine_similarity_with_mode(a: &str, b: &str, n: i64, mode: &str) -> Result<f64, String> {
+    let mode = ngram::Mode::parse(mode)?;
+    let profile_a = ngram::profile(a, n, mode)?;
+    let profile_b = ngram::profile(b, n, mode)?;
+    Ok(ngram::cosine(&profile_a, &profile_b))
+}
+
+#[udf]
+pub fn overlap_coefficient(a: &str, b: &str, n: i64) -> Result<f64, String> {
+    overlap_coefficient_with_mode(a, b, n, "char")
+}
+
+#[udf(name = "overlap_coefficient")]
+pub fn overlap_coefficient_with_mode(a: &str, b: &str, n: i64, mode: &str) -> Result<f64, String> {
+    let mode = ngram::Mode::parse(mode)?;
+    let profile_a = ngram::profile(a, n, mode)?;
+    let profile_b = ngram::profile(b, n, mode)?;
+    Ok(ngram::overlap(&profile_a, &profile_b))
+}
+
+#[udf]
+pub fn ngram_distance(a: &str, b: &str, n: i64) -> Result<i64, String> {
+    ngram_distance_with_mode(a, b, n, "char")
+}
+
+#[udf(name = "ngram_distance")]
+pub fn ngram_distance_with_mode(a: &str, b: &str, n: i64, mode: &str) -> Result<i64, String> {
+    let mode = ngram::Mode::parse(mode)?;
+    let profile_a = ngram::profile(a, n, mode)?;
+    let profile_b = ngram::profile(b, n, mode)?;
+    Ok(ngram::distance(&profile_a, &profile_b) as i64)
+}
+
+// Subsequence and substring measures count graphemes like the edit distances.
+#[udf]
+pub fn lcs_length(a: &str, b: &str) -> i64 {
+    alignment::lcs_len(&graphemes(a), &graphemes(b)) as i64
+}
+
+#[udf]
+pub fn longest_common_substring(a: &str, b: &str) -> String {
+    let a = graphemes(a);
+    let (start, len) = alignment::longest_common_substring(&a, &graphemes(b));
+    a[start..start + len].concat()
+}
+
+#[udf]
+pub fn longest_common_prefix(a: &str, b: &str) -> String {
+    let a = graphemes(a);
+    let len = alignment::common_prefix_len(&a, &graphemes(b));
+    a[..len].concat()
+}
+
+// Alignment scores default to +1 per match and -1 per mismatch or gap. The five
+// argument forms take the match, mismatch and gap scores, e.g. (a, b, 2, -1, -2).
+const DEFAULT_SCORING: Scoring = Scoring {
+    matched: 1.0,
+    mismatch: -1.0,
+    gap: -1.0,
+};
+
+#[udf]
+pub fn smith_waterman_score(a: &str, b: &str) -> f64 {
+    alignment::smith_waterman(&graphemes(a), &graphemes(b), DEFAULT_SCORING)
+}
+
+#[udf(name = "smith_waterman_score")]
+pub fn smith_waterman_score_with_scoring(
+    a: &str,
+    b: &str,
+    matched: f64,
+    mismatch: f64,
+    gap: f64,
+) -> f64 {
+    let scoring = Scoring {
+        matched,
+        mismatch,
+        gap,
+    };
+    alignment::smith_waterman(&graphemes(a), &graphemes(b), scoring)
+}
+
+#[udf]
+pub fn needleman_wunsch_score(a: &str, b: &str) -> f64 {
+    alignment::needleman_wunsch(&graphemes(a), &graphemes(b), DEFAULT_SCORING)
+}
+
+#[udf(name = "needleman_wunsch_score")]
+pub fn needleman_wunsch_score_with_scoring(
+    a: &str,
+    b: &str,
+    matched: f64,
+    mismatch: f64,
+    gap: f64,
+) -> f64 {
+    let scoring = Scoring {
+        matched,
+        mismatch,
+        gap,
+    };
+    alignment::needleman_wunsch(&graphemes(a), &graphemes(b), scoring)
 }
 
 #[cfg(test)]
@@ -67,4 +318,204 @@ mod tests {
         let result = hamming_distance("clickhouse", "click");
         assert_eq!(result, "Differing length arguments provided");
     }
+
+    #[test]
+    fn test_ratio() {
+        assert_eq!(ratio("this is a test", "this is a test"), 100.0);
+        assert_eq!(ratio("", ""), 100.0);
+        assert_eq!(ratio("abcd", "efgh"), 0.0);
+        assert_eq!(ratio("abcd", "abce"), 75.0);
+        assert_eq!(ratio("Smith", "smith"), 80.0);
+        assert_eq!(ratio_with_options("Smith", "smith", "lowercase"), Ok(100.0));
+    }
+
+    #[test]
+    fn test_ratio_with_invalid_options() {
+        let result = ratio_with_options("a", "b", "lowercase,uppercase");
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap(),
+            "Unknown preprocessing option: uppercase"
+        );
+    }
+
+    #[test]
+    fn test_partial_ratio() {
+        assert_eq!(partial_ratio("this is a test", "this is a test!"), 100.0);
+        assert_eq!(partial_ratio("new york mets", "the new york mets"), 100.0);
+        assert_eq!(partial_ratio("abcd", "xxabcxx"), 75.0);
+        assert_eq!(partial_ratio("", "abc"), 0.0);
+    }
+
+    #[test]
+    fn test_token_sort_ratio() {
+        assert_eq!(token_sort_ratio("Smith, John", "John Smith"), 100.0);
+        assert_eq!(
+            token_sort_ratio("fuzzy wuzzy was a bear", "wuzzy fuzzy was a bear"),
+            100.0
+        );
+        let result = token_sort_ratio_with_options("Smith, John", "John Smith", "none");
+        assert!(result.unwrap() < 100.0);
+    }
+
+    #[test]
+    fn test_token_set_ratio() {
+        assert_eq!(
+            token_set_ratio("fuzzy was a bear", "fuzzy fuzzy was a bear"),
+            100.0
+        );
+        let result = token_set_ratio(
+            "mariners vs angels",
+            "los angeles angels at seattle mariners",
+        );
+        assert!(result > 90.0 && result < 100.0);
+        assert_eq!(token_set_ratio("", "abc"), 0.0);
+    }
+
+    #[test]
+    fn test_weighted_ratio() {
+        assert_eq!(weighted_ratio("Smith, John", "John Smith"), 95.0);
+        assert_eq!(weighted_ratio("new york mets", "new york mets"), 100.0);
+        assert_eq!(weighted_ratio("", "new york mets"), 0.0);
+        let result = weighted_ratio("new york mets", "the wonderful new york mets");
+        assert!(result > 85.0 && result < 100.0);
+    }
+
+    #[test]
+    fn test_jaccard_similarity() {
+        assert_eq!(jaccard_similarity("night", "nacht", 2), Ok(1.0 / 7.0));
+        assert_eq!(jaccard_similarity("abc", "abc", 2), Ok(1.0));
+        assert_eq!(jaccard_similarity("", "", 2), Ok(1.0));
+        assert_eq!(jaccard_similarity("a", "b", 2), Ok(0.0));
+        assert_eq!(
+            jaccard_similarity_with_mode("the quick fox", "the lazy fox", 1, "word"),
+            Ok(0.5)
+        );
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert_eq!(cosine_similarity("abc", "abc", 2), Ok(1.0));
+        assert_eq!(cosine_similarity("abcd", "abxy", 2), Ok(1.0 / 3.0));
+        assert_eq!(cosine_similarity("abc", "", 2), Ok(0.0));
+        assert_eq!(
+            cosine_similarity_with_mode("a b a b", "a b", 2, "word"),
+            Ok(2.0 / 5.0_f64.sqrt())
+        );
+        let long = "a".repeat(100_000);
+        assert_eq!(cosine_similarity(&long, &long, 1), Ok(1.0));
+    }
+
+    #[test]
+    fn test_overlap_coefficient() {
+        assert_eq!(overlap_coefficient("abc", "abcdef", 2), Ok(1.0));
+        assert_eq!(overlap_coefficient("night", "nacht", 2), Ok(0.25));
+        assert_eq!(
+            overlap_coefficient_with_mode("new york", "new york city", 1, "word"),
+            Ok(1.0)
+        );
+    }
+
+    #[test]
+    fn test_ngram_distance() {
+        assert_eq!(ngram_distance("abcd", "abcd", 2), Ok(0));
+        assert_eq!(ngram_distance("night", "nacht", 2), Ok(6));
+        assert_eq!(ngram_distance_with_mode("a b c", "a b d", 1, "word"), Ok(2));
+    }
+
+    #[test]
+    fn test_ngram_invalid_arguments() {
+        assert_eq!(
+            jaccard_similarity("a", "b", 0),
+            Err("N-gram size must be positive, got n: 0".to_string())
+        );
+        assert_eq!(
+            ngram_distance_with_mode("a", "b", 2, "line"),
+            Err("Mode must be 'char' or 'word', got mode: line".to_string())
+        );
+    }
+
+    #[test]
+    fn test_distances_count_graphemes() {
+        // "e" followed by a combining acute accent is a single grapheme
+        assert_eq!(damerau_levenshtein_distance("cafe\u{301}", "cafe"), 1);
+        assert_eq!(osa_distance("👨‍👩‍👧", "👍"), 1);
+        assert_eq!(hamming_distance("👨‍👩‍👧x", "👍y"), "2");
+        assert_eq!(normalized_levenshtein_distance("👍👍", "👍"), 0.5);
+        assert_eq!(osa_distance("ab", "bca"), 3);
+        assert_eq!(osa_distance("abcd", "acbd"), 1);
+    }
+
+    #[test]
+    fn test_case_insensitive_variants() {
+        assert_eq!(damerau_levenshtein_distance_ci("Café", "cafe"), 0);
+        assert_eq!(damerau_levenshtein_distance_ci("Straße", "STRASSE"), 0);
+        assert_eq!(
+            normalized_levenshtein_distance_ci("Crème Brûlée", "creme brulee"),
+            1.0
+        );
+        assert_eq!(jaro_winkler_similarity_ci("ÉCOLE", "ecole"), 1.0);
+        assert_eq!(hamming_distance_ci("Ｆｏｏ", "foo"), "0");
+        assert_eq!(osa_distance_ci("Café", "cfae"), 1);
+    }
+
+    #[test]
+    fn test_ratio_with_unicode_options() {
+        assert_eq!(
+            ratio_with_options("Café", "cafe", "case_fold,strip_accents"),
+            Ok(100.0)
+        );
+        assert_eq!(ratio_with_options("ｆｉ", "fi", "nfkd"), Ok(100.0));
+        // ℡ decomposes to uppercase TEL, which must still be folded
+        assert_eq!(ratio_with_options("℡", "tel", "case_fold,nfkd"), Ok(100.0));
+        assert_eq!(damerau_levenshtein_distance_ci("Ⅻ", "xii"), 0);
+    }
+
+    #[test]
+    fn test_lcs_length() {
+        assert_eq!(lcs_length("ABCBDAB", "BDCABA"), 4);
+        assert_eq!(lcs_length("", "abc"), 0);
+        assert_eq!(lcs_length("a👨‍👩‍👧b", "a👨‍👩‍👧c"), 2);
+    }
+
+    #[test]
+    fn test_longest_common_substring() {
+        assert_eq!(
+            longest_common_substring("Apple iPhone 15 Pro 128GB", "iPhone 15 Pro Max"),
+            "iPhone 15 Pro "
+        );
+        assert_eq!(longest_common_substring("abcxyz", "xyzabc"), "abc");
+        assert_eq!(longest_common_substring("abc", "def"), "");
+    }
+
+    #[test]
+    fn test_longest_common_prefix() {
+        assert_eq!(
+            longest_common_prefix("user 42 logged in", "user 7 logged out"),
+            "user "
+        );
+        assert_eq!(longest_common_prefix("abc", "xbc"), "");
+        assert_eq!(longest_common_prefix("👨‍👩‍👧a", "👨‍👩‍👧b"), "👨‍👩‍👧");
+    }
+
+    #[test]
+    fn test_smith_waterman_score() {
+        assert_eq!(smith_waterman_score("xxxhelloyyy", "zzhellozz"), 5.0);
+        assert_eq!(smith_waterman_score("abc", "xyz"), 0.0);
+        assert_eq!(
+            smith_waterman_score_with_scoring("TGTTACGG", "GGTTGACTA", 3.0, -3.0, -2.0),
+            13.0
+        );
+    }
+
+    #[test]
+    fn test_needleman_wunsch_score() {
+        assert_eq!(needleman_wunsch_score("GATTACA", "GCATGCU"), 0.0);
+        assert_eq!(needleman_wunsch_score("abc", "abc"), 3.0);
+        assert_eq!(needleman_wunsch_score("", "abc"), -3.0);
+        assert_eq!(
+            needleman_wunsch_score_with_scoring("abc", "abd", 2.0, -1.0, -2.0),
+            3.0
+        );
+    }
 }
@@ -0,0 +1,102 @@
+// Set and multiset measures over character or word n-grams.
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    Char,
+    Word,
+}
+
+impl Mode {
+    pub fn parse(mode: &str) -> Result<Mode, String> {
+        match mode.trim().to_ascii_lowercase().as_str() {
+            "char" | "character" => Ok(Mode::Char),
+            "word" => Ok(Mode::Word),
+            _ => Err(format!("Mode must be 'char' or 'word', got mode: {}", mode)),
+        }
+    }
+}
+
+// Counts of each n-gram. Input shorter than n yields the whole input as its only n-gram.
+pub fn profile(text: &str, n: i64, mode: Mode) -> Result<HashMap<String, usize>, String> {
+    if n < 1 {
+        return Err(format!("N-gram size must be positive, got n: {}", n));
+    }
+    let n = n as usize;
+    let grams: Vec<String> = match mode {
+        Mode::Char => {
+            let chars: Vec<char> = text.chars().collect();
+            if chars.len() < n {
+                vec![chars.iter().collect()]
+            } else {
+                chars.windows(n).map(|w| w.iter().collect()).collect()
+            }
+        }
+        Mode::Word => {
+            let words: Vec<&str> = text.split_whitespace().collect();
+            if words.len() < n {
+                vec![words.join(" ")]
+            } else {
+                words.windows(n).map(|w| w.join(" ")).collect()
+            }
+        }
+    };
+
+    let mut counts = HashMap::new();
+    for gram in grams.into_iter().filter(|g| !g.is_empty()) {
+        *counts.entry(gram).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+fn intersection_size(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> usize {
+    a.keys().filter(|gram| b.contains_key(*gram)).count()
+}
+
+pub fn jaccard(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let intersection = intersection_size(a, b);
+    intersection as f64 / (a.len() + b.len() - intersection) as f64
+}
+
+pub fn overlap(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    intersection_size(a, b) as f64 / a.len().min(b.len()) as f64
+}
+
+pub fn cosine(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    // in f64, the products of long profiles overflow usize
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(gram, count)| b.get(gram).map(|other| *count as f64 * *other as f64))
+        .sum();
+    let squares = |p: &HashMap<String, usize>| p.values().map(|c| (*c as f64).powi(2)).sum::<f64>();
+    dot / (squares(a) * squares(b)).sqrt()
+}
+
+// Ukkonen's q-gram distance: the L1 distance between the two n-gram profiles.
+pub fn distance(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> usize {
+    let only_b: usize = b
+        .iter()
+        .filter(|(gram, _)| !a.contains_key(*gram))
+        .map(|(_, count)| count)
+        .sum();
+    let rest: usize = a
+        .iter()
+        .map(|(gram, count)| count.abs_diff(b.get(gram).copied().unwrap_or(0)))
+        .sum();
+    only_b + rest
+}
@@ -0,0 +1,82 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+// Preprocessing applied to both strings before they are compared. Options are
+// given as a comma separated list, e.g. "lowercase,strip_punctuation".
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Preprocess {
+    pub case_fold: bool,
+    pub nfkd: bool,
+    pub strip_accents: bool,
+    pub lowercase: bool,
+    pub strip_punctuation: bool,
+}
+
+impl Preprocess {
+    pub const NONE: Preprocess = Preprocess {
+        case_fold: false,
+        nfkd: false,
+        strip_accents: false,
+        lowercase: false,
+        strip_punctuation: false,
+    };
+
+    pub const FULL: Preprocess = Preprocess {
+        lowercase: true,
+        strip_punctuation: true,
+        ..Preprocess::NONE
+    };
+
+    pub const INSENSITIVE: Preprocess = Preprocess {
+        case_fold: true,
+        nfkd: true,
+        strip_accents: true,
+        ..Preprocess::NONE
+    };
+
+    pub fn parse(options: &str) -> Result<Preprocess, String> {
+        let mut preprocess = Preprocess::NONE;
+        for option in options.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            match option.to_ascii_lowercase().as_str() {
+                "none" => preprocess = Preprocess::NONE,
+                "full" => preprocess = Preprocess::FULL,
+                "insensitive" => preprocess = Preprocess::INSENSITIVE,
+                "case_fold" => preprocess.case_fold = true,
+                "nfkd" => preprocess.nfkd = true,
+                "strip_accents" => preprocess.strip_accents = true,
+                "lowercase" => preprocess.lowercase = true,
+                "strip_punctuation" => preprocess.strip_punctuation = true,
+                _ => return Err(format!("Unknown preprocessing option: {}", option)),
+            }
+        }
+        Ok(preprocess)
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut result = if self.nfkd || self.strip_accents {
+            // compatibility characters such as ℡ only become letters once
+            // decomposed, and accents can only be stripped then
+            text.nfkd().collect()
+        } else {
+            text.to_string()
+        };
+        if self.case_fold {
+            result = caseless::default_case_fold_str(&result);
+        }
+        if self.strip_accents {
+            result.retain(|c| !is_combining_mark(c));
+        }
+        if self.lowercase {
+            result = result.to_lowercase();
+        }
+        if self.strip_punctuation {
+            result = result
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+                .collect::<String>()
+                .trim()
+                .to_string();
+        }
+        result
+    }
+}
@@ -0,0 +1,295 @@
+use gandiva_rust_udf_macro::udf;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthChar;
+
+#[udf]
+fn normalize_nfc(text: &str) -> String {
+    text.nfc().collect()
+}
+
+#[udf]
+fn normalize_nfd(text: &str) -> String {
+    text.nfd().collect()
+}
+
+#[udf]
+fn normalize_nfkc(text: &str) -> String {
+    text.nfkc().collect()
+}
+
+#[udf]
+fn normalize_nfkd(text: &str) -> String {
+    text.nfkd().collect()
+}
+
+// Unicode default case folding, e.g. "Straße" => "strasse". Unlike lowercasing
+// it makes caseless comparisons work for all scripts.
+#[udf]
+fn case_fold(text: &str) -> String {
+    caseless::default_case_fold_str(text)
+}
+
+// Strips accents and other combining marks, e.g. "Ñandú" => "Nandu". Letters
+// that are not composed with a mark, such as ø or ł, are kept.
+#[udf]
+fn remove_diacritics(text: &str) -> String {
+    text.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .nfc()
+        .collect()
+}
+
+// Best effort ASCII transliteration of any script, e.g. "Москва" => "Moskva".
+#[udf]
+fn transliterate_to_ascii(text: &str) -> String {
+    deunicode::deunicode(text)
+}
+
+// The UTS #39 skeleton: two strings that look alike, such as "paypal" with a
+// Cyrillic а, have the same skeleton.
+#[udf]
+fn confusable_skeleton(text: &str) -> String {
+    unicode_security::skeleton(text).collect()
+}
+
+// User-perceived characters: an emoji sequence or a letter with combining marks
+// counts once.
+#[udf]
+fn grapheme_count(text: &str) -> i64 {
+    text.graphemes(true).count() as i64
+}
+
+// Like substr, start is 1-based and counts from the end when negative.
+#[udf(name = "grapheme_substring")]
+fn grapheme_substring_to_end(text: &str, start: i64) -> Result<String, String> {
+    grapheme_substring(text, start, i64::MAX)
+}
+
+#[udf(name = "grapheme_substring")]
+fn grapheme_substring(text: &str, start: i64, length: i64) -> Result<String, String> {
+    if length < 0 {
+        return Err(format!(
+            "Length must not be negative, got length: {}",
+            length
+        ));
+    }
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    let count = graphemes.len() as i64;
+    let from = match start {
+        0 => return Err("Start must not be 0, positions are 1-based".to_string()),
+        1.. => start - 1,
+        _ => (count + start).max(0),
+    };
+    let from = from.min(count) as usize;
+    let to = (from as i64).saturating_add(length).min(count) as usize;
+    Ok(graphemes[from..to].concat())
+}
+
+#[udf(name = "truncate_graphemes")]
+fn truncate_graphemes_plain(text: &str, n: i64) -> Result<String, String> {
+    truncate_graphemes(text, n, "")
+}
+
+// Cuts text to at most n graphemes, the ellipsis included, e.g.
+// ("Hello world", 8, "…") => "Hello w…".
+#[udf(name = "truncate_graphemes")]
+fn truncate_graphemes(text: &str, n: i64, ellipsis: &str) -> Result<String, String> {
+    if n < 0 {
+        return Err(format!("N must not be negative, got n: {}", n));
+    }
+    let n = n as usize;
+    let graphemes: Vec<&str> = text.graphemes(true).collect();
+    if graphemes.len() <= n {
+        return Ok(text.to_string());
+    }
+    let ellipsis_len = ellipsis.graphemes(true).count();
+    if ellipsis_len > n {
+        // no room for the ellipsis
+        return Ok(graphemes[..n].concat());
+    }
+    Ok(graphemes[..n - ellipsis_len].concat() + ellipsis)
+}
+
+// Terminal columns of a grapheme: East Asian wide characters and emoji take
+// two, combining marks and control characters none. Emoji sequences such as
+// flags or families are a single wide character.
+fn grapheme_width(grapheme: &str) -> usize {
+    let width: usize = grapheme.chars().map(|c| c.width().unwrap_or(0)).sum();
+    width.min(2)
+}
+
+fn text_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+#[udf]
+fn display_width(text: &str) -> i64 {
+    text_width(text) as i64
+}
+
+#[udf(name = "pad_display_width")]
+fn pad_display_width_left(text: &str, width: i64) -> Result<String, String> {
+    pad_display_width(text, width, "left")
+}
+
+const MAX_DISPLAY_WIDTH: i64 = 1024;
+
+// Pads text with spaces to the given display width. align is left, right or
+// center; text that is already wider is returned unchanged.
+#[udf(name = "pad_display_width")]
+fn pad_display_width(text: &str, width: i64, align: &str) -> Result<String, String> {
+    if !(0..=MAX_DISPLAY_WIDTH).contains(&width) {
+        return Err(format!(
+            "Width must be between 0 and {}, got width: {}",
+            MAX_DISPLAY_WIDTH, width
+        ));
+    }
+    let padding = (width as usize).saturating_sub(text_width(text));
+    let (left, right) = match align.to_ascii_lowercase().as_str() {
+        "left" => (0, padding),
+        "right" => (padding, 0),
+        "center" => (padding / 2, padding - padding / 2),
+        _ => {
+            return Err(format!(
+                "Align must be 'left', 'right' or 'center', got align: {}",
+                align
+            ))
+        }
+    };
+    Ok(format!("{}{}{}", " ".repeat(left), text, " ".repeat(right)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let composed = "\u{e9}";
+        let decomposed = "e\u{301}";
+        assert_eq!(normalize_nfc(decomposed), composed);
+        assert_eq!(normalize_nfd(composed), decomposed);
+        assert_eq!(normalize_nfkc("ﬁ²"), "fi2");
+        assert_eq!(normalize_nfkd("ﬁ\u{e9}"), "fie\u{301}");
+    }
+
+    #[test]
+    fn test_case_fold() {
+        assert_eq!(case_fold("Straße"), "strasse");
+        assert_eq!(case_fold("ΣΊΣΥΦΟΣ"), case_fold("σίσυφος"));
+    }
+
+    #[test]
+    fn test_remove_diacritics() {
+        assert_eq!(remove_diacritics("Ñandú"), "Nandu");
+        assert_eq!(remove_diacritics("Crème Brûlée"), "Creme Brulee");
+        assert_eq!(remove_diacritics("e\u{301}"), "e");
+        assert_eq!(remove_diacritics("København"), "København");
+    }
+
+    #[test]
+    fn test_transliterate_to_ascii() {
+        assert_eq!(transliterate_to_ascii("Ñandú"), "Nandu");
+        assert_eq!(transliterate_to_ascii("Москва"), "Moskva");
+        assert_eq!(transliterate_to_ascii("København"), "Kobenhavn");
+        assert_eq!(transliterate_to_ascii("plain"), "plain");
+    }
+
+    #[test]
+    fn test_confusable_skeleton() {
+        assert_eq!(confusable_skeleton("pаypal"), confusable_skeleton("paypal"));
+        assert_eq!(confusable_skeleton("ℝ𝓊𝓈𝓉"), confusable_skeleton("Rust"));
+        // Cyrillic р, а and у
+        assert_eq!(
+            confusable_skeleton("раураl"),
+            confusable_skeleton("paypal")
+        );
+        assert_ne!(confusable_skeleton("paypak"), confusable_skeleton("paypal"));
+    }
+
+    #[test]
+    fn test_grapheme_count() {
+        assert_eq!(grapheme_count(""), 0);
+        assert_eq!(grapheme_count("hello"), 5);
+        assert_eq!(grapheme_count("e\u{301}"), 1);
+        assert_eq!(grapheme_count("👨‍👩‍👧🇩🇪"), 2);
+    }
+
+    #[test]
+    fn test_grapheme_substring() {
+        let text = "a👍🏽ce\u{301}";
+        assert_eq!(grapheme_substring(text, 2, 1), Ok("👍🏽".to_string()));
+        assert_eq!(grapheme_substring(text, 3, 10), Ok("ce\u{301}".to_string()));
+        assert_eq!(grapheme_substring(text, -1, 1), Ok("e\u{301}".to_string()));
+        assert_eq!(grapheme_substring(text, -10, 2), Ok("a👍🏽".to_string()));
+        assert_eq!(grapheme_substring(text, 9, 2), Ok("".to_string()));
+        assert_eq!(
+            grapheme_substring_to_end(text, 2),
+            Ok("👍🏽ce\u{301}".to_string())
+        );
+        assert_eq!(
+            grapheme_substring(text, 0, 1),
+            Err("Start must not be 0, positions are 1-based".to_string())
+        );
+        assert!(grapheme_substring(text, 1, -1).is_err());
+    }
+
+    #[test]
+    fn test_truncate_graphemes() {
+        assert_eq!(
+            truncate_graphemes("Hello world", 8, "…"),
+            Ok("Hello w…".to_string())
+        );
+        assert_eq!(truncate_graphemes("Hello", 8, "…"), Ok("Hello".to_string()));
+        assert_eq!(truncate_graphemes("Hello", 2, "..."), Ok("He".to_string()));
+        assert_eq!(
+            truncate_graphemes_plain("🇩🇪🇫🇷🇮🇹", 2),
+            Ok("🇩🇪🇫🇷".to_string())
+        );
+        assert_eq!(
+            truncate_graphemes_plain("abc", -1),
+            Err("N must not be negative, got n: -1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("hello"), 5);
+        assert_eq!(display_width("你好"), 4);
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("👍🏽"), 2);
+        assert_eq!(display_width("👨‍👩‍👧"), 2);
+        assert_eq!(display_width("▏▎"), 2);
+    }
+
+    #[test]
+    fn test_pad_display_width() {
+        assert_eq!(pad_display_width_left("你好", 6), Ok("你好  ".to_string()));
+        assert_eq!(
+            pad_display_width("你好", 6, "right"),
+            Ok("  你好".to_string())
+        );
+        assert_eq!(
+            pad_display_width("ab", 5, "center"),
+            Ok(" ab  ".to_string())
+        );
+        assert_eq!(
+            pad_display_width_left("toolong", 3),
+            Ok("toolong".to_string())
+        );
+        assert_eq!(
+            pad_display_width_left("a", i64::MAX),
+            Err(format!(
+                "Width must be between 0 and 1024, got width: {}",
+                i64::MAX
+            ))
+        );
+        assert!(pad_display_width_left("a", -1).is_err());
+        assert_eq!(
+            pad_display_width("a", 3, "justify"),
+            Err("Align must be 'left', 'right' or 'center', got align: justify".to_string())
+        );
+    }
+}
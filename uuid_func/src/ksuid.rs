@@ -0,0 +1,62 @@
+// K-Sortable Unique IDs as generated by segmentio/ksuid: a 4 byte timestamp in
+// seconds since KSUID_EPOCH followed by 16 random bytes, base62 encoded in 27
+// characters.
+use rand::RngCore;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const KSUID_EPOCH: i64 = 1_400_000_000;
+const BYTES: usize = 20;
+const ENCODED_LEN: usize = 27;
+const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+pub fn generate() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    let mut bytes = [0u8; BYTES];
+    bytes[..4].copy_from_slice(&((now - KSUID_EPOCH) as u32).to_be_bytes());
+    rand::thread_rng().fill_bytes(&mut bytes[4..]);
+    encode(&bytes)
+}
+
+fn encode(bytes: &[u8; BYTES]) -> String {
+    // long division of the big endian number by 62, one digit at a time
+    let mut number = bytes.to_vec();
+    let mut digits = vec![b'0'; ENCODED_LEN];
+    for digit in digits.iter_mut().rev() {
+        let mut remainder = 0u32;
+        for byte in number.iter_mut() {
+            let value = remainder << 8 | *byte as u32;
+            *byte = (value / 62) as u8;
+            remainder = value % 62;
+        }
+        *digit = ALPHABET[remainder as usize];
+    }
+    String::from_utf8(digits).unwrap()
+}
+
+fn decode(text: &str) -> Option<[u8; BYTES]> {
+    if text.len() != ENCODED_LEN {
+        return None;
+    }
+    let mut bytes = [0u8; BYTES];
+    for c in text.bytes() {
+        let mut carry = ALPHABET.iter().position(|a| *a == c)? as u32;
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * 62 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(bytes)
+}
+
+// Unix timestamp of the KSUID in seconds.
+pub fn timestamp(text: &str) -> Result<i64, String> {
+    let bytes = decode(text).ok_or_else(|| format!("Invalid KSUID: {}", text))?;
+    let offset = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+    Ok(KSUID_EPOCH + offset as i64)
+}
@@ -1,10 +1,208 @@
+mod ksuid;
+
 use gandiva_rust_udf_macro::udf;
+use std::sync::OnceLock;
+use ulid::Ulid;
+use uuid::Uuid;
 
 #[udf]
-fn uuid() -> String {
+pub fn uuid() -> String {
     uuid::Uuid::new_v4().to_string()
 }
 
+// Time-ordered, so new values sort after older ones and index well.
+#[udf]
+pub fn uuid_v7() -> String {
+    Uuid::now_v7().to_string()
+}
+
+// Version 1 UUIDs need a node id. Rather than exposing a MAC address, a random
+// one with the multicast bit set is used, as RFC 4122 allows.
+fn node_id() -> &'static [u8; 6] {
+    static NODE_ID: OnceLock<[u8; 6]> = OnceLock::new();
+    NODE_ID.get_or_init(|| {
+        let random = Uuid::new_v4();
+        let mut node_id = [0u8; 6];
+        node_id.copy_from_slice(&random.as_bytes()[10..]);
+        node_id[0] |= 0x01;
+        node_id
+    })
+}
+
+#[udf]
+pub fn uuid_v1() -> String {
+    Uuid::now_v1(node_id()).to_string()
+}
+
+// The namespace is one of dns, url, oid, x500 or a UUID.
+fn namespace(namespace: &str) -> Result<Uuid, String> {
+    match namespace.to_ascii_lowercase().as_str() {
+        "dns" => Ok(Uuid::NAMESPACE_DNS),
+        "url" => Ok(Uuid::NAMESPACE_URL),
+        "oid" => Ok(Uuid::NAMESPACE_OID),
+        "x500" => Ok(Uuid::NAMESPACE_X500),
+        _ => Uuid::parse_str(namespace).map_err(|_| {
+            format!(
+                "Namespace must be dns, url, oid, x500 or a UUID, got namespace: {}",
+                namespace
+            )
+        }),
+    }
+}
+
+#[udf]
+pub fn uuid_v3(namespace_uuid: &str, name: &str) -> Result<String, String> {
+    Ok(Uuid::new_v3(&namespace(namespace_uuid)?, name.as_bytes()).to_string())
+}
+
+#[udf]
+pub fn uuid_v5(namespace_uuid: &str, name: &str) -> Result<String, String> {
+    Ok(Uuid::new_v5(&namespace(namespace_uuid)?, name.as_bytes()).to_string())
+}
+
+// Accepts the hyphenated, simple, braced and urn forms in either case.
+fn parse(text: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(text.trim()).map_err(|_| format!("Invalid UUID: {}", text))
+}
+
+#[udf]
+pub fn is_valid_uuid(text: &str) -> bool {
+    parse(text).is_ok()
+}
+
+#[udf]
+pub fn uuid_version(text: &str) -> Result<i64, String> {
+    Ok(parse(text)?.get_version_num() as i64)
+}
+
+// Milliseconds since the Unix epoch stored in a version 7 UUID.
+#[udf]
+pub fn uuid_v7_timestamp(text: &str) -> Result<i64, String> {
+    let uuid = parse(text)?;
+    if uuid.get_version_num() != 7 {
+        return Err(format!("Not a version 7 UUID: {}", text));
+    }
+    let bytes = uuid.as_bytes();
+    Ok(bytes[..6].iter().fold(0i64, |ms, b| ms << 8 | *b as i64))
+}
+
+#[udf]
+pub fn uuid_to_hex(text: &str) -> Result<String, String> {
+    Ok(parse(text)?.simple().to_string())
+}
+
+// The lowercase hyphenated form.
+#[udf]
+pub fn uuid_normalize(text: &str) -> Result<String, String> {
+    Ok(parse(text)?.hyphenated().to_string())
+}
+
+#[udf]
+pub fn ulid() -> String {
+    Ulid::new().to_string()
+}
+
+// Milliseconds since the Unix epoch stored in a ULID.
+#[udf]
+pub fn ulid_timestamp(text: &str) -> Result<i64, String> {
+    let ulid = Ulid::from_string(text.trim()).map_err(|_| format!("Invalid ULID: {}", text))?;
+    Ok(ulid.timestamp_ms() as i64)
+}
+
+const MAX_NANOID_SIZE: i64 = 1024;
+
+#[udf(name = "nanoid")]
+pub fn nanoid_default() -> String {
+    nanoid::nanoid!()
+}
+
+#[udf(name = "nanoid")]
+pub fn nanoid_with_size(size: i64) -> Result<String, String> {
+    let alphabet: String = nanoid::alphabet::SAFE.iter().collect();
+    nanoid_with_alphabet(size, &alphabet)
+}
+
+#[udf(name = "nanoid")]
+pub fn nanoid_with_alphabet(size: i64, alphabet: &str) -> Result<String, String> {
+    if !(1..=MAX_NANOID_SIZE).contains(&size) {
+        return Err(format!(
+            "Size must be between 1 and {}, got size: {}",
+            MAX_NANOID_SIZE, size
+        ));
+    }
+    let alphabet: Vec<char> = alphabet.chars().collect();
+    // nanoid counts the id length in bytes and picks characters by a single byte
+    if alphabet.len() < 2 || alphabet.len() > 255 || !alphabet.iter().all(char::is_ascii) {
+        return Err(format!(
+            "Alphabet must have between 2 and 255 ASCII characters, got alphabet: {}",
+            alphabet.iter().collect::<String>()
+        ));
+    }
+    Ok(nanoid::format(
+        nanoid::rngs::default,
+        &alphabet,
+        size as usize,
+    ))
+}
+
+#[udf]
+pub fn ksuid() -> String {
+    ksuid::generate()
+}
+
+// Unix timestamp of a KSUID in seconds.
+#[udf]
+pub fn ksuid_timestamp(text: &str) -> Result<i64, String> {
+    ksuid::timestamp(text.trim())
+}
+
+// Twitter style Snowflake IDs: 41 bits of milliseconds since epoch, 10 bits of
+// worker id and a 12 bit sequence. Twitter's own epoch is 1288834974657.
+fn snowflake_parts(id: i64) -> Result<(i64, i64, i64), String> {
+    if id < 0 {
+        return Err(format!("Invalid Snowflake ID: {}", id));
+    }
+    Ok((id >> 22, id >> 12 & 0x3ff, id & 0xfff))
+}
+
+fn snowflake_time(offset: i64, epoch: i64) -> Result<i64, String> {
+    offset.checked_add(epoch).ok_or_else(|| {
+        format!(
+            "Epoch must be at most {} for this ID, got epoch: {}",
+            i64::MAX - offset,
+            epoch
+        )
+    })
+}
+
+#[udf]
+pub fn snowflake_timestamp(id: i64, epoch: i64) -> Result<i64, String> {
+    let (offset, _, _) = snowflake_parts(id)?;
+    snowflake_time(offset, epoch)
+}
+
+#[udf]
+pub fn snowflake_worker(id: i64) -> Result<i64, String> {
+    Ok(snowflake_parts(id)?.1)
+}
+
+#[udf]
+pub fn snowflake_sequence(id: i64) -> Result<i64, String> {
+    Ok(snowflake_parts(id)?.2)
+}
+
+// All parts as a JSON object, e.g. {"timestamp":1288834974657,"worker":0,"sequence":0}.
+#[udf]
+pub fn snowflake_decode(id: i64, epoch: i64) -> Result<String, String> {
+    let (offset, worker, sequence) = snowflake_parts(id)?;
+    Ok(format!(
+        "{{\"timestamp\":{},\"worker\":{},\"sequence\":{}}}",
+        snowflake_time(offset, epoch)?,
+        worker,
+        sequence
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -14,4 +212,175 @@ mod tests {
         let result = uuid();
         assert_eq!(result.len(), 36);
     }
+
+    #[test]
+    fn test_uuid_v7() {
+        let first = uuid_v7();
+        assert_eq!(first.len(), 36);
+        assert_eq!(uuid_version(&first), Ok(7));
+        assert!(uuid_v7_timestamp(&uuid_v7()).unwrap() >= uuid_v7_timestamp(&first).unwrap());
+    }
+
+    #[test]
+    fn test_uuid_v1() {
+        let result = uuid_v1();
+        assert_eq!(uuid_version(&result), Ok(1));
+        assert_eq!(result[24..], uuid_v1()[24..]);
+    }
+
+    #[test]
+    fn test_uuid_v3_v5() {
+        assert_eq!(
+            uuid_v3("dns", "www.example.com"),
+            Ok("5df41881-3aed-3515-88a7-2f4a814cf09e".to_string())
+        );
+        assert_eq!(
+            uuid_v5("dns", "www.example.com"),
+            Ok("2ed6657d-e927-568b-95e1-2665a8aea6a2".to_string())
+        );
+        assert_eq!(
+            uuid_v5("6ba7b810-9dad-11d1-80b4-00c04fd430c8", "www.example.com"),
+            uuid_v5("DNS", "www.example.com")
+        );
+        assert_eq!(
+            uuid_v5("example", "x"),
+            Err(
+                "Namespace must be dns, url, oid, x500 or a UUID, got namespace: example"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_is_valid_uuid() {
+        assert!(is_valid_uuid("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+        assert!(is_valid_uuid("{67E55044-10B1-426F-9247-BB680E5FE0C8}"));
+        assert!(is_valid_uuid(
+            "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8"
+        ));
+        assert!(is_valid_uuid("67e5504410b1426f9247bb680e5fe0c8"));
+        assert!(!is_valid_uuid("67e55044-10b1-426f-9247-bb680e5fe0c"));
+        assert!(!is_valid_uuid(""));
+    }
+
+    #[test]
+    fn test_uuid_version() {
+        assert_eq!(uuid_version("67e55044-10b1-426f-9247-bb680e5fe0c8"), Ok(4));
+        assert_eq!(uuid_version("00000000-0000-0000-0000-000000000000"), Ok(0));
+        assert_eq!(uuid_version("nope"), Err("Invalid UUID: nope".to_string()));
+    }
+
+    #[test]
+    fn test_uuid_v7_timestamp() {
+        assert_eq!(
+            uuid_v7_timestamp("017f22e2-79b0-7cc3-98c4-dc0c0c07398f"),
+            Ok(1645557742000)
+        );
+        assert_eq!(
+            uuid_v7_timestamp("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            Err("Not a version 7 UUID: 67e55044-10b1-426f-9247-bb680e5fe0c8".to_string())
+        );
+    }
+
+    #[test]
+    fn test_uuid_to_hex_and_normalize() {
+        assert_eq!(
+            uuid_to_hex("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            Ok("67e5504410b1426f9247bb680e5fe0c8".to_string())
+        );
+        assert_eq!(
+            uuid_normalize("{67E55044-10B1-426F-9247-BB680E5FE0C8}"),
+            Ok("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string())
+        );
+        assert_eq!(
+            uuid_normalize(" urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8 "),
+            Ok("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string())
+        );
+        assert!(uuid_normalize("67e55044").is_err());
+    }
+
+    #[test]
+    fn test_ulid() {
+        let result = ulid();
+        assert_eq!(result.len(), 26);
+        assert!(ulid_timestamp(&result).unwrap() > 1_600_000_000_000);
+        assert_eq!(
+            ulid_timestamp("01ARZ3NDEKTSV4RRFFQ69G5FAV"),
+            Ok(1469922850259)
+        );
+        assert_eq!(
+            ulid_timestamp("01ARZ3NDEK"),
+            Err("Invalid ULID: 01ARZ3NDEK".to_string())
+        );
+    }
+
+    #[test]
+    fn test_nanoid() {
+        assert_eq!(nanoid_default().len(), 21);
+        assert_eq!(nanoid_with_size(10).unwrap().len(), 10);
+        let id = nanoid_with_alphabet(32, "abc").unwrap();
+        assert_eq!(id.len(), 32);
+        assert!(id.chars().all(|c| "abc".contains(c)));
+        assert_eq!(
+            nanoid_with_size(0),
+            Err("Size must be between 1 and 1024, got size: 0".to_string())
+        );
+        assert_eq!(
+            nanoid_with_alphabet(8, "a"),
+            Err(
+                "Alphabet must have between 2 and 255 ASCII characters, got alphabet: a"
+                    .to_string()
+            )
+        );
+        assert!(nanoid_with_alphabet(8, "äö").is_err());
+    }
+
+    #[test]
+    fn test_ksuid() {
+        let result = ksuid();
+        assert_eq!(result.len(), 27);
+        assert!(ksuid_timestamp(&result).unwrap() > 1_600_000_000);
+        // from segmentio/ksuid's README
+        assert_eq!(
+            ksuid_timestamp("0ujtsYcgvSTl8PAuAdqWYSMnLOv"),
+            Ok(1507608047)
+        );
+        assert_eq!(
+            ksuid_timestamp("000000000000000000000000000"),
+            Ok(1400000000)
+        );
+        assert_eq!(
+            ksuid_timestamp("zzzzzzzzzzzzzzzzzzzzzzzzzzz"),
+            Err("Invalid KSUID: zzzzzzzzzzzzzzzzzzzzzzzzzzz".to_string())
+        );
+        assert!(ksuid_timestamp("0ujtsYcgvSTl8PAuAdqWYSMnLO_").is_err());
+    }
+
+    #[test]
+    fn test_snowflake() {
+        let epoch = 1288834974657;
+        let id = (1577836800000 - epoch) << 22 | 5 << 12 | 7;
+        assert_eq!(snowflake_timestamp(id, epoch), Ok(1577836800000));
+        assert_eq!(snowflake_worker(id), Ok(5));
+        assert_eq!(snowflake_sequence(id), Ok(7));
+        assert_eq!(
+            snowflake_decode(id, epoch),
+            Ok("{\"timestamp\":1577836800000,\"worker\":5,\"sequence\":7}".to_string())
+        );
+        assert_eq!(
+            snowflake_worker(-1),
+            Err("Invalid Snowflake ID: -1".to_string())
+        );
+        // 1 << 22 has a timestamp offset of 1
+        assert_eq!(
+            snowflake_timestamp(1 << 22, i64::MAX),
+            Err(format!(
+                "Epoch must be at most {} for this ID, got epoch: {}",
+                i64::MAX - 1,
+                i64::MAX
+            ))
+        );
+        assert!(snowflake_decode(1 << 22, i64::MAX).is_err());
+        assert_eq!(snowflake_timestamp(1 << 22, i64::MAX - 1), Ok(i64::MAX));
+    }
 }